@@ -8,6 +8,15 @@ use crate::module_name::ModuleName;
 use crate::supported_py_version::get_target_py_version;
 use crate::typeshed::{parse_typeshed_versions, TypeshedVersions, TypeshedVersionsQueryResult};
 
+mod completion;
+mod site_packages;
+
+pub(crate) use completion::{complete_submodules, SubmoduleCompletion};
+pub(crate) use site_packages::{
+    discover_site_packages, expand_editable_install_roots, expand_pth_files,
+    site_packages_search_paths,
+};
+
 /// Enumeration of the different kinds of search paths type checkers are expected to support.
 ///
 /// N.B. Although we don't implement `Ord` for this enum, they are ordered in terms of the
@@ -110,15 +119,20 @@ impl ModuleResolutionPathBuf {
     }
 
     #[must_use]
-    pub(crate) fn is_regular_package(&self, db: &dyn Db, search_path: &Self) -> bool {
+    pub(crate) fn is_regular_package(&self, db: &dyn Db, search_path: &Self) -> ResolutionOutcome {
         ModuleResolutionPathRef::from(self).is_regular_package(db, search_path)
     }
 
     #[must_use]
-    pub(crate) fn is_directory(&self, db: &dyn Db, search_path: &Self) -> bool {
+    pub(crate) fn is_directory(&self, db: &dyn Db, search_path: &Self) -> ResolutionOutcome {
         ModuleResolutionPathRef::from(self).is_directory(db, search_path)
     }
 
+    #[must_use]
+    pub(crate) fn is_namespace_package(&self, db: &dyn Db, search_path: &Self) -> bool {
+        ModuleResolutionPathRef::from(self).is_namespace_package(db, search_path)
+    }
+
     #[must_use]
     pub(crate) fn with_pyi_extension(&self) -> Self {
         ModuleResolutionPathRef::from(self).with_pyi_extension()
@@ -184,43 +198,126 @@ enum ModuleResolutionPathRefInner<'a> {
     SitePackages(&'a FileSystemPath),
 }
 
+/// The outcome of trying to resolve something against a custom typeshed's `stdlib/VERSIONS` file.
+///
+/// Mirrors the `Determined`/`Undetermined` distinction Rust's own resolver uses so that a
+/// dependency that isn't available *yet* doesn't get hard-committed to "does not exist": a custom
+/// typeshed whose `VERSIONS` file is missing or fails to parse shouldn't panic the checker, nor
+/// should it be treated the same as a module that's definitively absent, since the user may simply
+/// not have finished setting up (or repairing) their custom typeshed directory yet.
+///
+/// The `ModuleResolutionPathBuf` and `ModuleResolutionPathRef` wrappers of
+/// `is_directory`/`is_regular_package` return this enum rather than a plain `bool`.
+/// `resolve_namespace_package`'s candidate classification calls `.is_resolved()`, which folds
+/// `Indeterminate` into "doesn't contribute" (an `Indeterminate` candidate can only occur for
+/// `StandardLibrary` search paths). As a result, [`resolve_module`] reports a standard-library
+/// module whose `VERSIONS` file couldn't be read as [`ModuleResolutionResult::NotFound`]; the
+/// `Indeterminate` outcome does not reach its result.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum ResolutionOutcome {
+    /// Resolution succeeded.
+    Resolved,
+    /// Resolution definitely failed.
+    NotFound,
+    /// Resolution depends on typeshed version data that isn't currently available (a missing or
+    /// invalid `VERSIONS` file in a custom typeshed), so it can't be decided either way yet. This
+    /// should suppress "unresolved import" diagnostics that would otherwise fire spuriously, and
+    /// taints any standard-library-dependent inference; the caller may retry once the custom
+    /// typeshed directory is repaired.
+    Indeterminate,
+}
+
+impl ResolutionOutcome {
+    #[must_use]
+    fn from_bool(resolved: bool) -> Self {
+        if resolved {
+            Self::Resolved
+        } else {
+            Self::NotFound
+        }
+    }
+
+    #[must_use]
+    pub(crate) fn is_resolved(self) -> bool {
+        matches!(self, Self::Resolved)
+    }
+}
+
+/// The result of resolving a dotted module name against the search paths configured for a
+/// resolution context (`Extra`, `FirstParty`, `StandardLibrary`, or `SitePackages`).
+///
+/// Most modules resolve to a single file or regular package, but [PEP 420] implicit namespace
+/// packages have no single location: every contributing directory, across every search path,
+/// forms part of the same module.
+///
+/// [PEP 420]: https://peps.python.org/pep-0420/
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(crate) enum ModuleResolution {
+    /// A regular module or package, resolved to the single file or `__init__`-containing
+    /// directory that provides it.
+    Single(ModuleResolutionPathBuf),
+    /// A PEP 420 implicit namespace package, merged from every contributing directory, in
+    /// search-path priority order.
+    NamespacePackage(Vec<ModuleResolutionPathBuf>),
+}
+
 impl<'a> ModuleResolutionPathRefInner<'a> {
+    /// Loads and parses a custom typeshed's `stdlib/VERSIONS` file.
+    ///
+    /// This module only consumes [`crate::typeshed`]'s parsing and lookup; it doesn't define the
+    /// `VERSIONS` grammar or the lookup rule itself, so treat the following as the contract this
+    /// code relies on rather than as independently re-verified behavior: one entry per line,
+    /// `module[.submodule]: MIN[-MAX]` (versions are `MAJOR.MINOR`; a missing `MAX` means the
+    /// module is still present in the newest supported version), blank lines and trailing `#`
+    /// comments allowed, and [`TypeshedVersions::query_module`] resolving a dotted module name by
+    /// longest matching prefix (a submodule entry overrides its parent's range; an absent entry
+    /// means the name isn't a tracked stdlib module at all, which callers should treat as "not
+    /// found" rather than "out of range" so resolution falls through to other search paths). See
+    /// [`parse_typeshed_versions`] and [`TypeshedVersions::query_module`] for the authoritative
+    /// behavior.
+    ///
+    /// Returns `None` (rather than panicking) if the file is missing or fails to parse; callers
+    /// should treat that as [`ResolutionOutcome::Indeterminate`], not as "module not found".
     #[must_use]
     fn load_typeshed_versions<'db>(
         db: &'db dyn Db,
         stdlib_root: &FileSystemPath,
-    ) -> &'db TypeshedVersions {
+    ) -> Option<&'db TypeshedVersions> {
         let versions_path = stdlib_root.join("VERSIONS");
-        let Some(versions_file) = system_path_to_file(db.upcast(), &versions_path) else {
-            todo!(
-                "Still need to figure out how to handle VERSIONS files being deleted \
-                from custom typeshed directories! Expected a file to exist at {versions_path}"
-            )
-        };
-        // TODO(Alex/Micha): If VERSIONS is invalid,
-        // this should invalidate not just the specific module resolution we're currently attempting,
-        // but all type inference that depends on any standard-library types.
-        // Unwrapping here is not correct...
-        parse_typeshed_versions(db, versions_file).as_ref().unwrap()
-    }
-
+        let versions_file = system_path_to_file(db.upcast(), &versions_path)?;
+        parse_typeshed_versions(db, versions_file).as_ref().ok()
+    }
+
+    /// This, together with [`Self::is_regular_package`], *is* the live VERSIONS-gated stdlib
+    /// filtering: for a `StandardLibrary` candidate, a directory on disk only resolves if
+    /// `stdlib/VERSIONS` (via [`Self::load_typeshed_versions`]) says the target Python version is
+    /// in the module's supported range, falling through to [`ResolutionOutcome::NotFound`] (or
+    /// `Indeterminate`, if `VERSIONS` itself couldn't be read) otherwise, through every caller of
+    /// this method (`ModuleResolutionPathBuf`/`ModuleResolutionPathRef`'s wrappers,
+    /// `resolve_namespace_package`, and `resolve_module`). There is no separate filtering step
+    /// elsewhere in the resolver to add.
     #[must_use]
-    fn is_directory(&self, db: &dyn Db, search_path: Self) -> bool {
+    fn is_directory(&self, db: &dyn Db, search_path: Self) -> ResolutionOutcome {
         match (self, search_path) {
-            (Self::Extra(path), Self::Extra(_)) => db.file_system().is_directory(path),
-            (Self::FirstParty(path), Self::FirstParty(_)) => db.file_system().is_directory(path),
-            (Self::SitePackages(path), Self::SitePackages(_)) => db.file_system().is_directory(path),
+            (Self::Extra(path), Self::Extra(_))
+            | (Self::FirstParty(path), Self::FirstParty(_))
+            | (Self::SitePackages(path), Self::SitePackages(_)) => {
+                ResolutionOutcome::from_bool(db.file_system().is_directory(path))
+            }
             (Self::StandardLibrary(path), Self::StandardLibrary(stdlib_root)) => {
                 let Some(module_name) = ModuleResolutionPathRef(*self).to_module_name() else {
-                    return false;
+                    return ResolutionOutcome::NotFound;
+                };
+                let Some(typeshed_versions) = Self::load_typeshed_versions(db, stdlib_root)
+                else {
+                    return ResolutionOutcome::Indeterminate;
                 };
-                let typeshed_versions = Self::load_typeshed_versions(db, stdlib_root);
                 match typeshed_versions.query_module(&module_name, get_target_py_version(db)) {
                     TypeshedVersionsQueryResult::Exists
                     | TypeshedVersionsQueryResult::MaybeExists => {
-                        db.file_system().is_directory(path)
+                        ResolutionOutcome::from_bool(db.file_system().is_directory(path))
                     }
-                    TypeshedVersionsQueryResult::DoesNotExist => false,
+                    TypeshedVersionsQueryResult::DoesNotExist => ResolutionOutcome::NotFound,
                 }
             }
             (path, root) => unreachable!(
@@ -230,29 +327,36 @@ impl<'a> ModuleResolutionPathRefInner<'a> {
     }
 
     #[must_use]
-    fn is_regular_package(&self, db: &dyn Db, search_path: Self) -> bool {
+    fn is_regular_package(&self, db: &dyn Db, search_path: Self) -> ResolutionOutcome {
         match (self, search_path) {
             (Self::Extra(path), Self::Extra(_))
             | (Self::FirstParty(path), Self::FirstParty(_))
             | (Self::SitePackages(path), Self::SitePackages(_)) => {
                 let file_system = db.file_system();
-                file_system.exists(&path.join("__init__.py"))
-                    || file_system.exists(&path.join("__init__.pyi"))
+                ResolutionOutcome::from_bool(
+                    file_system.exists(&path.join("__init__.py"))
+                        || file_system.exists(&path.join("__init__.pyi")),
+                )
             }
             // Unlike the other variants:
             // (1) Account for VERSIONS
             // (2) Only test for `__init__.pyi`, not `__init__.py`
             (Self::StandardLibrary(path), Self::StandardLibrary(stdlib_root)) => {
                 let Some(module_name) = ModuleResolutionPathRef(*self).to_module_name() else {
-                    return false;
+                    return ResolutionOutcome::NotFound;
+                };
+                let Some(typeshed_versions) = Self::load_typeshed_versions(db, stdlib_root)
+                else {
+                    return ResolutionOutcome::Indeterminate;
                 };
-                let typeshed_versions = Self::load_typeshed_versions(db, stdlib_root);
                 match typeshed_versions.query_module(&module_name, get_target_py_version(db)) {
                     TypeshedVersionsQueryResult::Exists
                     | TypeshedVersionsQueryResult::MaybeExists => {
-                        db.file_system().exists(&path.join("__init__.pyi"))
+                        ResolutionOutcome::from_bool(
+                            db.file_system().exists(&path.join("__init__.pyi")),
+                        )
                     }
-                    TypeshedVersionsQueryResult::DoesNotExist => false,
+                    TypeshedVersionsQueryResult::DoesNotExist => ResolutionOutcome::NotFound,
                 }
             }
             (path, root) => unreachable!(
@@ -261,6 +365,58 @@ impl<'a> ModuleResolutionPathRefInner<'a> {
         }
     }
 
+    /// Returns `true` if `self` is a directory that could contribute to a
+    /// [PEP 420](https://peps.python.org/pep-0420/) implicit namespace package: it exists on disk
+    /// but has no `__init__.py`/`__init__.pyi` of its own.
+    ///
+    /// A directory can be both absent of a regular package *and* still not be a namespace
+    /// contributor to a caller that already found a regular package for the same name on a
+    /// higher-priority search path; that precedence is the caller's responsibility (see
+    /// `ModuleResolutionPathRef::resolve_namespace_package`, consumed in turn by [`resolve_module`],
+    /// this crate's single top-level caller), not something a single path can know about in
+    /// isolation.
+    #[must_use]
+    fn is_namespace_package(&self, db: &dyn Db, search_path: Self) -> bool {
+        // `Indeterminate` (only possible for `StandardLibrary`, which doesn't support namespace
+        // packages in practice) conservatively counts as "not a namespace package" here, rather
+        // than as "namespace package" or propagating the indeterminacy further: namespace-package
+        // discovery is purely a convenience for merging first-party/site-packages roots, not
+        // something stdlib version data should block.
+        namespace_package_is_valid(
+            self.is_directory(db, search_path).is_resolved(),
+            self.is_regular_package(db, search_path).is_resolved(),
+        )
+    }
+
+    /// For a `SitePackages` path, returns the path of the sibling
+    /// [PEP 561](https://peps.python.org/pep-0561/#stub-only-packages) stub-only distribution
+    /// (`<top_level>-stubs`) that would provide type information for this module, if `self` lies
+    /// inside `site_packages`; `<top_level>` is the first path component below `site_packages`
+    /// (its file stem, for a single-file top-level module). Returns `None` for every other
+    /// variant, and for a path outside `site_packages`.
+    #[must_use]
+    fn to_stub_package(&self, site_packages: &FileSystemPath) -> Option<FileSystemPathBuf> {
+        let Self::SitePackages(path) = self else {
+            return None;
+        };
+
+        let relative = path.strip_prefix(site_packages).ok()?;
+        let mut components = relative.components();
+        let first = components.next()?;
+
+        let top_level_name = if components.next().is_some() {
+            // `first` is a package directory (`foo/bar.py` -> top-level package `foo`).
+            first.as_str()
+        } else {
+            // `first` is the whole relative path: a single-file top-level module (`foo.py`).
+            FileSystemPath::new(first.as_str())
+                .file_stem()
+                .unwrap_or_else(|| first.as_str())
+        };
+
+        Some(site_packages.join(format!("{top_level_name}-stubs")))
+    }
+
     #[must_use]
     pub(crate) fn to_module_name(self) -> Option<ModuleName> {
         let (fs_path, skip_final_part) = match self {
@@ -271,10 +427,25 @@ impl<'a> ModuleResolutionPathRefInner<'a> {
             Self::StandardLibrary(path) => (path, path.ends_with("__init__.pyi")),
         };
 
-        let parent_components = fs_path
+        let mut parent_components: Vec<&str> = fs_path
             .parent()?
             .components()
-            .map(|component| component.as_str());
+            .map(|component| component.as_str())
+            .collect();
+
+        // A PEP 561 (https://peps.python.org/pep-0561/#stub-only-packages) stub-only
+        // distribution's directory is named `{name}-stubs`, but it provides type information
+        // *for* the runtime package `{name}`, not for a separate `{name}-stubs` package, so the
+        // importable module name drops the `-stubs` suffix from the leading path component.
+        if matches!(self, Self::SitePackages(_)) {
+            if let Some(top_level) = parent_components.first_mut() {
+                if let Some(stripped) = top_level.strip_suffix("-stubs") {
+                    *top_level = stripped;
+                }
+            }
+        }
+
+        let parent_components = parent_components.into_iter();
 
         if skip_final_part {
             ModuleName::from_components(parent_components)
@@ -326,7 +497,6 @@ impl<'a> ModuleResolutionPathRefInner<'a> {
         }
     }
 
-    #[cfg(test)]
     #[must_use]
     fn to_path_buf(self) -> ModuleResolutionPathBufInner {
         match self {
@@ -353,6 +523,90 @@ impl<'a> ModuleResolutionPathRefInner<'a> {
     }
 }
 
+/// Pure precedence rule for [`ModuleResolutionPathRef::is_valid_stub_package`], split out from the
+/// filesystem probing so it's unit-testable without a real `Db`: given what's already known about
+/// a `-stubs` directory's contents, decides whether it's a valid PEP 561 stub-only distribution.
+#[must_use]
+fn stub_package_is_valid(has_init_pyi: bool, is_directory: bool, has_init_py: bool) -> bool {
+    has_init_pyi || (is_directory && !has_init_py)
+}
+
+/// Pure rule for [`ModuleResolutionPathRefInner::is_namespace_package`], split out from the
+/// filesystem probing so it's unit-testable without a real `Db`: a directory contributes to a
+/// [PEP 420](https://peps.python.org/pep-0420/) namespace package if it exists but isn't itself a
+/// regular package.
+#[must_use]
+fn namespace_package_is_valid(is_directory: bool, is_regular_package: bool) -> bool {
+    is_directory && !is_regular_package
+}
+
+/// The outcome of applying [`ModuleResolutionPathRef::resolve_namespace_package`]'s precedence
+/// rule, in terms of indices into the priority-ordered candidate list it was given.
+#[derive(Debug, Clone, PartialEq, Eq)]
+enum NamespacePrecedenceOutcome {
+    /// The candidate at this index is a regular package; the scan stopped there.
+    RegularPackage(usize),
+    /// No regular package was found; these indices (in priority order) are namespace contributors.
+    NamespacePackage(Vec<usize>),
+    /// Nothing at all contributed.
+    NotFound,
+}
+
+/// Pure control flow for [`ModuleResolutionPathRef::resolve_namespace_package`], split out from
+/// the filesystem probing so the "stop at the first regular package, otherwise accumulate
+/// namespace contributors" precedence rule is unit-testable without real search paths or a `Db`.
+///
+/// `classifications` pairs, for each candidate in priority order, whether it's a regular package
+/// and whether it's a namespace-package contributor.
+#[must_use]
+fn resolve_namespace_precedence(
+    classifications: impl IntoIterator<Item = (bool, bool)>,
+) -> NamespacePrecedenceOutcome {
+    let mut namespace_roots = Vec::new();
+
+    for (index, (is_regular_package, is_namespace_package)) in
+        classifications.into_iter().enumerate()
+    {
+        if is_regular_package {
+            return NamespacePrecedenceOutcome::RegularPackage(index);
+        }
+        if is_namespace_package {
+            namespace_roots.push(index);
+        }
+    }
+
+    if namespace_roots.is_empty() {
+        NamespacePrecedenceOutcome::NotFound
+    } else {
+        NamespacePrecedenceOutcome::NamespacePackage(namespace_roots)
+    }
+}
+
+/// Classic Levenshtein edit distance between `a` and `b`, computed with the standard two-row
+/// dynamic-programming table rather than a full `O(len(a) * len(b))` matrix.
+#[must_use]
+fn levenshtein_distance(a: &str, b: &str) -> usize {
+    let b_chars: Vec<char> = b.chars().collect();
+
+    // `previous_row[j]` holds the distance between the prefix of `a` processed so far and the
+    // first `j` characters of `b`; it starts as if `a` were empty.
+    let mut previous_row: Vec<usize> = (0..=b_chars.len()).collect();
+    let mut current_row = vec![0; b_chars.len() + 1];
+
+    for (i, a_char) in a.chars().enumerate() {
+        current_row[0] = i + 1;
+        for (j, &b_char) in b_chars.iter().enumerate() {
+            let substitution_cost = usize::from(a_char != b_char);
+            current_row[j + 1] = (previous_row[j + 1] + 1) // deletion from `a`
+                .min(current_row[j] + 1) // insertion into `a`
+                .min(previous_row[j] + substitution_cost); // substitution, tracked via the diagonal
+        }
+        std::mem::swap(&mut previous_row, &mut current_row);
+    }
+
+    previous_row[b_chars.len()]
+}
+
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub(crate) struct ModuleResolutionPathRef<'a>(ModuleResolutionPathRefInner<'a>);
 
@@ -393,20 +647,144 @@ impl<'a> ModuleResolutionPathRef<'a> {
     }
 
     #[must_use]
-    pub(crate) fn is_directory(&self, db: &dyn Db, search_path: impl Into<Self>) -> bool {
+    pub(crate) fn is_directory(&self, db: &dyn Db, search_path: impl Into<Self>) -> ResolutionOutcome {
         self.0.is_directory(db, search_path.into().0)
     }
 
     #[must_use]
-    pub(crate) fn is_regular_package(&self, db: &dyn Db, search_path: impl Into<Self>) -> bool {
+    pub(crate) fn is_regular_package(&self, db: &dyn Db, search_path: impl Into<Self>) -> ResolutionOutcome {
         self.0.is_regular_package(db, search_path.into().0)
     }
 
+    #[must_use]
+    pub(crate) fn is_namespace_package(&self, db: &dyn Db, search_path: impl Into<Self>) -> bool {
+        self.0.is_namespace_package(db, search_path.into().0)
+    }
+
+    #[must_use]
+    pub(crate) fn to_stub_package(&self, site_packages: &FileSystemPath) -> Option<FileSystemPathBuf> {
+        self.0.to_stub_package(site_packages)
+    }
+
+    /// Whether `stub_package` (as produced by [`Self::to_stub_package`]) is a valid
+    /// [PEP 561](https://peps.python.org/pep-0561/#stub-only-packages) stub-only distribution that
+    /// should be preferred over the runtime package it stubs.
+    ///
+    /// `-stubs` packages are stub-only by naming convention alone. The directory qualifies if it
+    /// has an `__init__.pyi` (a bare `py.typed` marker isn't checked, since that marker is for
+    /// packages that ship *inline* types, not stub-only distributions), or if it is a directory
+    /// with no `__init__.py`, which is treated as a namespace stub package. The directory's other
+    /// contents are not inspected, so an empty directory, or one containing only non-`.pyi` files,
+    /// also qualifies as a namespace stub. A directory with an `__init__.py` but no
+    /// `__init__.pyi` (a regular, non-stub package under the `-stubs` name) does not qualify.
+    #[must_use]
+    pub(crate) fn is_valid_stub_package(db: &dyn Db, stub_package: &FileSystemPath) -> bool {
+        stub_package_is_valid(
+            db.file_system().exists(&stub_package.join("__init__.pyi")),
+            db.file_system().is_directory(stub_package),
+            db.file_system().exists(&stub_package.join("__init__.py")),
+        )
+    }
+
+    /// Resolves a module across every search path it might live on, accounting for
+    /// [PEP 420](https://peps.python.org/pep-0420/) implicit namespace packages.
+    ///
+    /// This is the per-name resolution step [`resolve_module`] (this crate's single top-level
+    /// resolution entry point) calls once it has built the candidate path under every search root;
+    /// it isn't meant to be called directly for a real resolution.
+    ///
+    /// `candidates` is the module's path under each search root that might provide it, paired
+    /// with that root, in search-path priority order (highest priority first). The search does
+    /// not stop at the first directory found: a directory with no `__init__.py`/`__init__.pyi`
+    /// doesn't resolve anything on its own, so scanning continues onto lower-priority search
+    /// paths, accumulating every such directory into a merged [`ModuleResolution::NamespacePackage`].
+    /// A regular package (one containing `__init__`) found on any search path takes precedence
+    /// and terminates the scan immediately, discarding any namespace directories accumulated so
+    /// far, matching CPython's finder: a concrete package always wins over a bare namespace,
+    /// regardless of which of the two was found first.
+    ///
+    /// Returns `None` if no candidate contributes anything at all.
+    #[must_use]
+    pub(crate) fn resolve_namespace_package<'b>(
+        db: &dyn Db,
+        candidates: impl IntoIterator<Item = (ModuleResolutionPathRef<'b>, ModuleResolutionPathRef<'b>)>,
+    ) -> Option<ModuleResolution> {
+        let candidates: Vec<_> = candidates.into_iter().collect();
+        let classifications = candidates.iter().map(|(candidate, search_path)| {
+            (
+                candidate.is_regular_package(db, *search_path).is_resolved(),
+                candidate.is_namespace_package(db, *search_path),
+            )
+        });
+
+        match resolve_namespace_precedence(classifications) {
+            NamespacePrecedenceOutcome::RegularPackage(index) => {
+                Some(ModuleResolution::Single(candidates[index].0.to_path_buf()))
+            }
+            NamespacePrecedenceOutcome::NamespacePackage(indices) => {
+                Some(ModuleResolution::NamespacePackage(
+                    indices
+                        .into_iter()
+                        .map(|index| candidates[index].0.to_path_buf())
+                        .collect(),
+                ))
+            }
+            NamespacePrecedenceOutcome::NotFound => None,
+        }
+    }
+
     #[must_use]
     pub(crate) fn to_module_name(self) -> Option<ModuleName> {
         self.0.to_module_name()
     }
 
+    /// Given a `name` that failed to resolve against any search path, and the dotted module names
+    /// that *do* exist on disk (gathered by the caller from walking the
+    /// `Extra`/`FirstParty`/`StandardLibrary`/`SitePackages` roots, highest-priority root first),
+    /// returns the closest match by Levenshtein edit distance, for a "did you mean `foo.bar`?"
+    /// diagnostic hint. Mirrors the `lev_distance`-based fallback suggestions Rust's own name
+    /// resolver uses.
+    ///
+    /// Only a candidate within `max(1, len / 3)` edits of `name` is considered close enough to
+    /// suggest, where `len` is the length of `name`'s final dotted component; if several are
+    /// equally close, the one appearing earliest in `candidates` (i.e. from the highest-priority
+    /// search path) wins.
+    #[must_use]
+    pub(crate) fn suggest_similar_module_name<'b>(
+        name: &ModuleName,
+        candidates: impl IntoIterator<Item = &'b ModuleName>,
+    ) -> Option<&'b ModuleName> {
+        let failed_last_component = name.as_str().rsplit('.').next().unwrap_or(name.as_str());
+        let threshold = (failed_last_component.len() / 3).max(1);
+
+        let mut best: Option<(&ModuleName, usize)> = None;
+
+        for candidate in candidates {
+            let candidate_last_component = candidate
+                .as_str()
+                .rsplit('.')
+                .next()
+                .unwrap_or(candidate.as_str());
+
+            // Take the smaller of the distance between the final components and the distance
+            // between the full dotted paths, so that e.g. `foo.bar` vs `foobar` still counts as
+            // close even though `bar`/`foobar` alone are three edits apart.
+            let distance = levenshtein_distance(failed_last_component, candidate_last_component)
+                .min(levenshtein_distance(name.as_str(), candidate.as_str()));
+
+            if distance > threshold {
+                continue;
+            }
+
+            match best {
+                Some((_, best_distance)) if distance >= best_distance => {}
+                _ => best = Some((candidate, distance)),
+            }
+        }
+
+        best.map(|(candidate, _)| candidate)
+    }
+
     #[must_use]
     pub(crate) fn with_pyi_extension(&self) -> ModuleResolutionPathBuf {
         ModuleResolutionPathBuf(self.0.with_pyi_extension())
@@ -438,12 +816,136 @@ impl<'a> ModuleResolutionPathRef<'a> {
         }
     }
 
-    #[cfg(test)]
+    #[must_use]
     pub(crate) fn to_path_buf(self) -> ModuleResolutionPathBuf {
         ModuleResolutionPathBuf(self.0.to_path_buf())
     }
 }
 
+/// The result of [`resolve_module`]: either a module resolved against the given search paths, or
+/// -- since this crate has no diagnostic-emission machinery of its own -- enough information for a
+/// caller that does (e.g. an "unresolved import" diagnostic) to report a "did you mean" hint.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(crate) enum ModuleResolutionResult {
+    Resolved(ModuleResolution),
+    NotFound {
+        /// The closest-matching importable name actually found across the search paths, if
+        /// [`ModuleResolutionPathRef::suggest_similar_module_name`] found one close enough.
+        suggestion: Option<ModuleName>,
+    },
+}
+
+/// Resolves a dotted `name` against every root in `search_paths` (in priority order; see
+/// [`site_packages_search_paths`] for assembling the `SitePackages` portion of this list), the
+/// crate's single end-to-end entry point: builds `name`'s candidate path under each root and hands
+/// them to [`ModuleResolutionPathRef::resolve_namespace_package`] to account for [PEP 420]
+/// namespace packages, falling back to an edit-distance "did you mean" suggestion
+/// ([`ModuleResolutionPathRef::suggest_similar_module_name`]) computed from the names actually
+/// importable across those same search paths if nothing resolved.
+///
+/// [PEP 420]: https://peps.python.org/pep-0420/
+#[must_use]
+pub(crate) fn resolve_module(
+    db: &dyn Db,
+    name: &ModuleName,
+    search_paths: &[ModuleResolutionPathBuf],
+) -> ModuleResolutionResult {
+    let candidate_bufs: Vec<ModuleResolutionPathBuf> = search_paths
+        .iter()
+        .map(|search_path| build_candidate(db, name, search_path))
+        .collect();
+
+    let candidates = candidate_bufs
+        .iter()
+        .zip(search_paths)
+        .map(|(candidate, search_path)| {
+            (
+                ModuleResolutionPathRef::from(candidate),
+                ModuleResolutionPathRef::from(search_path),
+            )
+        });
+
+    match ModuleResolutionPathRef::resolve_namespace_package(db, candidates) {
+        Some(resolution) => ModuleResolutionResult::Resolved(resolution),
+        None => ModuleResolutionResult::NotFound {
+            suggestion: suggest_for_unresolved(db, name, search_paths),
+        },
+    }
+}
+
+/// Builds `name`'s candidate path under `search_path` for [`resolve_module`], preferring a PEP 561
+/// `-stubs` sibling over the runtime package it stubs when `search_path` is a `SitePackages` root
+/// and one is present (see [`ModuleResolutionPathRef::to_stub_package`]).
+#[must_use]
+fn build_candidate(
+    db: &dyn Db,
+    name: &ModuleName,
+    search_path: &ModuleResolutionPathBuf,
+) -> ModuleResolutionPathBuf {
+    let mut components = name.as_str().split('.');
+    let Some(first) = components.next() else {
+        return search_path.clone();
+    };
+
+    let mut candidate = search_path.clone();
+    candidate.push(first);
+
+    if let ModuleResolutionPathBufInner::SitePackages(site_packages) = &search_path.0 {
+        if let Some(stub_root) =
+            ModuleResolutionPathRef::from(&candidate).to_stub_package(site_packages)
+        {
+            if ModuleResolutionPathRef::is_valid_stub_package(db, &stub_root) {
+                if let Some(stub_candidate) = ModuleResolutionPathBuf::site_packages(stub_root) {
+                    candidate = stub_candidate;
+                }
+            }
+        }
+    }
+
+    for component in components {
+        candidate.push(component);
+    }
+
+    candidate
+}
+
+/// Falls back to an edit-distance "did you mean" suggestion for a `name` that failed to resolve
+/// against any of `search_paths`, built from every importable submodule [`complete_submodules`]
+/// finds under `name`'s own parent prefix (or, for a single-component `name`, every top-level
+/// importable name) across those same search paths.
+///
+/// Suggesting only within `name`'s own parent keeps the suggestion a plausible typo of the actual
+/// import the user wrote (e.g. `collections.abs` only ever suggests `collections.*` siblings, not
+/// an unrelated top-level package that merely happens to be a similar edit distance away).
+#[must_use]
+fn suggest_for_unresolved(
+    db: &dyn Db,
+    name: &ModuleName,
+    search_paths: &[ModuleResolutionPathBuf],
+) -> Option<ModuleName> {
+    let parent = name
+        .as_str()
+        .rsplit_once('.')
+        .and_then(|(parent, _)| ModuleName::from_components(parent.split('.')));
+
+    let completions = complete_submodules(db, search_paths, parent.as_ref());
+
+    let candidates: Vec<ModuleName> = completions
+        .into_iter()
+        .filter_map(|completion| match &parent {
+            Some(parent) => ModuleName::from_components(
+                parent
+                    .as_str()
+                    .split('.')
+                    .chain(std::iter::once(completion.name.as_str())),
+            ),
+            None => ModuleName::from_components(std::iter::once(completion.name.as_str())),
+        })
+        .collect();
+
+    ModuleResolutionPathRef::suggest_similar_module_name(name, &candidates).cloned()
+}
+
 impl<'a> From<&'a ModuleResolutionPathBufInner> for ModuleResolutionPathRefInner<'a> {
     #[inline]
     fn from(value: &'a ModuleResolutionPathBufInner) -> Self {
@@ -809,4 +1311,197 @@ mod tests {
     fn invalid_stdlib_join_too_many_extensions() {
         stdlib_path_test_case("foo").push("bar.py.pyi");
     }
+
+    #[test]
+    fn resolution_outcome_from_bool() {
+        assert_eq!(ResolutionOutcome::from_bool(true), ResolutionOutcome::Resolved);
+        assert_eq!(ResolutionOutcome::from_bool(false), ResolutionOutcome::NotFound);
+    }
+
+    #[test]
+    fn resolution_outcome_is_resolved() {
+        assert!(ResolutionOutcome::Resolved.is_resolved());
+        assert!(!ResolutionOutcome::NotFound.is_resolved());
+        assert!(!ResolutionOutcome::Indeterminate.is_resolved());
+    }
+
+    #[test]
+    fn levenshtein_distance_identical() {
+        assert_eq!(levenshtein_distance("foo", "foo"), 0);
+    }
+
+    #[test]
+    fn levenshtein_distance_typo() {
+        assert_eq!(levenshtein_distance("coluors", "colours"), 2);
+    }
+
+    #[test]
+    fn levenshtein_distance_empty() {
+        assert_eq!(levenshtein_distance("", "abc"), 3);
+    }
+
+    #[test]
+    fn stub_package_valid_with_init_pyi() {
+        assert!(stub_package_is_valid(true, true, false));
+        // An `__init__.pyi` makes a stub package valid regardless of a (nonsensical, but
+        // hypothetical) stray `__init__.py` sitting alongside it.
+        assert!(stub_package_is_valid(true, true, true));
+    }
+
+    #[test]
+    fn stub_package_valid_as_namespace_stub() {
+        assert!(stub_package_is_valid(false, true, false));
+    }
+
+    #[test]
+    fn stub_package_invalid_when_missing() {
+        assert!(!stub_package_is_valid(false, false, false));
+    }
+
+    #[test]
+    fn stub_package_invalid_when_really_a_regular_package() {
+        assert!(!stub_package_is_valid(false, true, true));
+    }
+
+    #[test]
+    fn namespace_package_valid_when_directory_without_init() {
+        assert!(namespace_package_is_valid(true, false));
+    }
+
+    #[test]
+    fn namespace_package_invalid_when_regular_package() {
+        assert!(!namespace_package_is_valid(true, true));
+    }
+
+    #[test]
+    fn namespace_package_invalid_when_missing() {
+        assert!(!namespace_package_is_valid(false, false));
+    }
+
+    #[test]
+    fn namespace_precedence_stops_at_first_regular_package() {
+        let outcome =
+            resolve_namespace_precedence([(false, true), (true, false), (false, true)]);
+        assert_eq!(outcome, NamespacePrecedenceOutcome::RegularPackage(1));
+    }
+
+    #[test]
+    fn namespace_precedence_accumulates_namespace_contributors() {
+        let outcome =
+            resolve_namespace_precedence([(false, true), (false, false), (false, true)]);
+        assert_eq!(
+            outcome,
+            NamespacePrecedenceOutcome::NamespacePackage(vec![0, 2])
+        );
+    }
+
+    #[test]
+    fn namespace_precedence_not_found_when_nothing_contributes() {
+        let outcome = resolve_namespace_precedence([(false, false), (false, false)]);
+        assert_eq!(outcome, NamespacePrecedenceOutcome::NotFound);
+    }
+
+    #[test]
+    fn to_stub_package_for_package_submodule() {
+        let site_packages = FileSystemPath::new("/site-packages");
+        let module = ModuleResolutionPathRef::site_packages("/site-packages/foo/bar.py").unwrap();
+        assert_eq!(
+            module.to_stub_package(site_packages),
+            Some(FileSystemPathBuf::from("/site-packages/foo-stubs"))
+        );
+    }
+
+    #[test]
+    fn to_stub_package_for_top_level_module() {
+        let site_packages = FileSystemPath::new("/site-packages");
+        let module = ModuleResolutionPathRef::site_packages("/site-packages/foo.py").unwrap();
+        assert_eq!(
+            module.to_stub_package(site_packages),
+            Some(FileSystemPathBuf::from("/site-packages/foo-stubs"))
+        );
+    }
+
+    #[test]
+    fn to_stub_package_none_outside_site_packages() {
+        let site_packages = FileSystemPath::new("/site-packages");
+        let module = ModuleResolutionPathRef::first_party("foo.py").unwrap();
+        assert_eq!(module.to_stub_package(site_packages), None);
+    }
+
+    fn module_name(path: &str) -> ModuleName {
+        ModuleResolutionPathRef::first_party(path)
+            .unwrap()
+            .to_module_name()
+            .unwrap()
+    }
+
+    #[test]
+    fn suggest_similar_module_name_typo() {
+        let failed = module_name("collections/abs");
+        let candidates = [module_name("collections/abc"), module_name("itertools")];
+
+        assert_eq!(
+            ModuleResolutionPathRef::suggest_similar_module_name(&failed, &candidates),
+            Some(&candidates[0])
+        );
+    }
+
+    #[test]
+    fn suggest_similar_module_name_nothing_close_enough() {
+        let failed = module_name("foo");
+        let candidates = [module_name("completely_unrelated_name")];
+
+        assert_eq!(
+            ModuleResolutionPathRef::suggest_similar_module_name(&failed, &candidates),
+            None
+        );
+    }
+
+    #[test]
+    fn stub_only_package_module_name_drops_stubs_suffix() {
+        assert_debug_snapshot!(
+            ModuleResolutionPathRef::site_packages("foo-stubs/bar.pyi")
+                .unwrap()
+                .to_module_name(),
+            @r###"
+        Some(
+            ModuleName(
+                "foo.bar",
+            ),
+        )
+        "###
+        );
+    }
+
+    #[test]
+    fn stub_only_package_module_name_dunder_init() {
+        assert_debug_snapshot!(
+            ModuleResolutionPathRef::site_packages("foo-stubs/__init__.pyi")
+                .unwrap()
+                .to_module_name(),
+            @r###"
+        Some(
+            ModuleName(
+                "foo",
+            ),
+        )
+        "###
+        );
+    }
+
+    #[test]
+    fn non_stub_only_package_module_name_unaffected() {
+        assert_debug_snapshot!(
+            ModuleResolutionPathRef::site_packages("foo/bar.pyi")
+                .unwrap()
+                .to_module_name(),
+            @r###"
+        Some(
+            ModuleName(
+                "foo.bar",
+            ),
+        )
+        "###
+        );
+    }
 }
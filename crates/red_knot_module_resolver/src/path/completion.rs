@@ -0,0 +1,173 @@
+//! Importable-submodule completion, for editors driving `import foo.bar.<caret>`- or
+//! `from foo.bar import <caret>`-style autocompletion.
+//!
+//! Borrows the approach editors use for completing `mod` declarations: scan the matching
+//! directory on disk and collapse the handful of shapes a single submodule can take into one
+//! candidate. Names already written or already in scope are not filtered out. Unlike a `mod`
+//! completion, a dotted prefix
+//! can be satisfied by several different search paths at once (a first-party package shadowing
+//! part of a namespace package in site-packages, say), so candidates are merged by name across
+//! every search path before being returned.
+
+use rustc_hash::FxHashMap;
+
+use crate::db::Db;
+use crate::module_name::ModuleName;
+use crate::path::ModuleResolutionPathBuf;
+
+/// One importable name directly under a (possibly empty) dotted prefix.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(crate) struct SubmoduleCompletion {
+    /// The bare (non-dotted) name of the submodule, e.g. `bar` for `foo.bar`.
+    pub(crate) name: String,
+    /// Whether this name resolves to a package (a directory, potentially containing further
+    /// submodules) as opposed to a single-file module.
+    pub(crate) is_package: bool,
+}
+
+/// Lists every importable child of `prefix` across `search_paths`, in no particular order.
+///
+/// `search_paths` is every root that could contribute to `prefix` (`StandardLibrary`, `Extra`,
+/// `FirstParty`, and `SitePackages` roots alike), in priority order; `prefix` is `None` to list
+/// each root's own top-level importable names. `baz.py`, `baz.pyi`, `baz/__init__.py`, and
+/// `baz/__init__.pyi` all collapse to the single candidate `baz`; a name seen on more than one
+/// search path is only returned once, keeping the `is_package` classification from whichever
+/// search path it was encountered on first (matching the precedence `search_paths` is already
+/// given in).
+#[must_use]
+pub(crate) fn complete_submodules<'a>(
+    db: &dyn Db,
+    search_paths: impl IntoIterator<Item = &'a ModuleResolutionPathBuf>,
+    prefix: Option<&ModuleName>,
+) -> Vec<SubmoduleCompletion> {
+    let mut is_package_by_name: FxHashMap<String, bool> = FxHashMap::default();
+
+    for search_path in search_paths {
+        let mut directory = search_path.clone();
+        if let Some(prefix) = prefix {
+            for component in prefix.as_str().split('.') {
+                directory.push(component);
+            }
+        }
+
+        let Ok(entries) = db.file_system().read_directory(directory.as_ref()) else {
+            continue;
+        };
+
+        for entry in entries.flatten() {
+            let Some(file_name) = entry.file_name() else {
+                continue;
+            };
+
+            let is_directory = db.file_system().is_directory(&entry);
+            let has_init = is_directory
+                && (db.file_system().exists(&entry.join("__init__.py"))
+                    || db.file_system().exists(&entry.join("__init__.pyi")));
+
+            let Some((name, is_package)) = classify_completion_entry(
+                file_name,
+                is_directory,
+                has_init,
+                entry.file_stem(),
+                entry.extension(),
+            ) else {
+                continue;
+            };
+
+            is_package_by_name.entry(name).or_insert(is_package);
+        }
+    }
+
+    is_package_by_name
+        .into_iter()
+        .map(|(name, is_package)| SubmoduleCompletion { name, is_package })
+        .collect()
+}
+
+/// Classifies a single directory entry found while scanning a search path for completions, split
+/// out from the filesystem probing in [`complete_submodules`] so the shape-collapsing rule is
+/// unit-testable without a real `Db`. Returns the `(name, is_package)` candidate it contributes,
+/// or `None` if this entry isn't importable at all.
+///
+/// `has_init` is only meaningful when `is_directory` is `true`; `stem`/`extension` are only
+/// meaningful when `is_directory` is `false`.
+#[must_use]
+fn classify_completion_entry(
+    file_name: &str,
+    is_directory: bool,
+    has_init: bool,
+    stem: Option<&str>,
+    extension: Option<&str>,
+) -> Option<(String, bool)> {
+    if is_directory {
+        // A directory only contributes a completion if it's itself importable, i.e. has an
+        // `__init__.py`/`__init__.pyi` (PEP 420 namespace directories are intentionally not
+        // offered here, since suggesting them as completions before they're known to contain
+        // anything importable would be noisy).
+        has_init.then(|| (file_name.to_string(), true))
+    } else {
+        if !matches!(extension, Some("py" | "pyi")) {
+            return None;
+        }
+        let stem = stem?;
+        if stem == "__init__" {
+            // The package itself, not a submodule of it; already represented by the directory
+            // entry that contains this file.
+            return None;
+        }
+        Some((stem.to_string(), false))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn classify_completion_entry_package_with_init() {
+        assert_eq!(
+            classify_completion_entry("bar", true, true, None, None),
+            Some(("bar".to_string(), true))
+        );
+    }
+
+    #[test]
+    fn classify_completion_entry_namespace_directory_is_skipped() {
+        assert_eq!(
+            classify_completion_entry("bar", true, false, None, None),
+            None
+        );
+    }
+
+    #[test]
+    fn classify_completion_entry_py_module() {
+        assert_eq!(
+            classify_completion_entry("baz.py", false, false, Some("baz"), Some("py")),
+            Some(("baz".to_string(), false))
+        );
+    }
+
+    #[test]
+    fn classify_completion_entry_pyi_module() {
+        assert_eq!(
+            classify_completion_entry("baz.pyi", false, false, Some("baz"), Some("pyi")),
+            Some(("baz".to_string(), false))
+        );
+    }
+
+    #[test]
+    fn classify_completion_entry_dunder_init_file_is_skipped() {
+        assert_eq!(
+            classify_completion_entry("__init__.py", false, false, Some("__init__"), Some("py")),
+            None
+        );
+    }
+
+    #[test]
+    fn classify_completion_entry_non_python_file_is_skipped() {
+        assert_eq!(
+            classify_completion_entry("README.md", false, false, Some("README"), Some("md")),
+            None
+        );
+    }
+}
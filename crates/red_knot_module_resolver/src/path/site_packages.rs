@@ -0,0 +1,276 @@
+//! Discovery of the `site-packages` directory for an (active or project-local) virtual
+//! environment, and expansion of the `.pth` files found there.
+//!
+//! This mirrors the "infer the search path instead of requiring the user to spell it out"
+//! approach Cargo/rustpkg take for crate search paths, adapted to Python's site machinery: rather
+//! than requiring a `SitePackages` [`ModuleResolutionPathBuf`] to be constructed by hand, we try to
+//! find the interpreter's real `site-packages` directory and register it (plus whatever additional
+//! roots its `.pth` files point at) automatically.
+
+use ruff_db::file_system::{FileSystemPath, FileSystemPathBuf};
+
+use crate::db::Db;
+use crate::path::ModuleResolutionPathBuf;
+
+/// The directory inside a POSIX virtualenv under which an interpreter version directory (e.g.
+/// `python3.11`) holds `site-packages`.
+const POSIX_SITE_PACKAGES: &str = "lib";
+const WINDOWS_SITE_PACKAGES: &[&str] = &["Lib", "site-packages"];
+
+/// Attempts to discover the `site-packages` directory to use for `workspace_root`, in priority
+/// order:
+///
+/// 1. `$VIRTUAL_ENV/lib/pythonX.Y/site-packages` (POSIX) or `$VIRTUAL_ENV/Lib/site-packages`
+///    (Windows), if the `VIRTUAL_ENV` environment variable names an activated virtualenv.
+/// 2. A `.venv` directory directly inside `workspace_root` containing a `pyvenv.cfg` marker file,
+///    the layout `python -m venv` creates by default.
+/// 3. `interpreter`'s own virtualenv, for a configured (not necessarily activated) interpreter:
+///    `<venv_root>/bin/python*` (POSIX) or `<venv_root>/Scripts/python.exe` (Windows) is the
+///    layout every venv tool produces, so the interpreter's grandparent directory is the venv
+///    root, provided it too has a `pyvenv.cfg` marker. This crate has no mechanism for actually
+///    invoking an interpreter to ask it for its real `sys.path`, so this approximates that by
+///    reading the layout on disk instead; a system interpreter (e.g. `/usr/bin/python3`, which
+///    isn't a venv at all) is correctly rejected by the `pyvenv.cfg` check even though its path
+///    happens to also end in a `bin` component.
+///
+/// Returns `None` if none of the three are found; callers should fall back to requiring the user
+/// to configure `site-packages` explicitly in that case.
+#[must_use]
+pub(crate) fn discover_site_packages(
+    db: &dyn Db,
+    workspace_root: &FileSystemPath,
+    interpreter: Option<&FileSystemPath>,
+) -> Option<FileSystemPathBuf> {
+    if let Ok(virtual_env) = std::env::var("VIRTUAL_ENV") {
+        let venv_root = FileSystemPath::new(&virtual_env);
+        if let Some(site_packages) = site_packages_in_venv(db, venv_root) {
+            return Some(site_packages);
+        }
+    }
+
+    let dot_venv = workspace_root.join(".venv");
+    if db.file_system().exists(&dot_venv.join("pyvenv.cfg")) {
+        if let Some(site_packages) = site_packages_in_venv(db, &dot_venv) {
+            return Some(site_packages);
+        }
+    }
+
+    if let Some(venv_root) = interpreter.and_then(venv_root_for_interpreter) {
+        if db.file_system().exists(&venv_root.join("pyvenv.cfg")) {
+            if let Some(site_packages) = site_packages_in_venv(db, &venv_root) {
+                return Some(site_packages);
+            }
+        }
+    }
+
+    None
+}
+
+/// Given the path to a Python interpreter executable, returns the virtualenv root it would belong
+/// to under the standard venv layout (`<venv_root>/bin/python*` on POSIX, `<venv_root>/Scripts/
+/// python.exe` on Windows), split out from the filesystem probing in [`discover_site_packages`] so
+/// the layout rule itself is unit-testable without a real `Db`.
+///
+/// Returns `None` for an interpreter that isn't nested directly under a `bin` or `Scripts`
+/// directory, since it then can't be a venv interpreter under this layout at all.
+#[must_use]
+fn venv_root_for_interpreter(interpreter: &FileSystemPath) -> Option<FileSystemPathBuf> {
+    let bin_dir = interpreter.parent()?;
+    if matches!(bin_dir.file_name()?, "bin" | "Scripts") {
+        Some(bin_dir.parent()?.to_path_buf())
+    } else {
+        None
+    }
+}
+
+/// Given the root of a virtualenv (the directory containing `pyvenv.cfg`), locates its
+/// `site-packages` directory.
+#[must_use]
+fn site_packages_in_venv(db: &dyn Db, venv_root: &FileSystemPath) -> Option<FileSystemPathBuf> {
+    let windows_site_packages = venv_root.join(WINDOWS_SITE_PACKAGES[0]).join(WINDOWS_SITE_PACKAGES[1]);
+    if db.file_system().is_directory(&windows_site_packages) {
+        return Some(windows_site_packages);
+    }
+
+    // POSIX venvs nest `site-packages` under a Python-version-specific directory
+    // (`lib/python3.11/site-packages`); since we don't know the interpreter's exact version here,
+    // take the first `python*` directory we find under `lib`.
+    let lib = venv_root.join(POSIX_SITE_PACKAGES);
+    for candidate in db.file_system().read_directory(&lib).ok()? {
+        let candidate = candidate.ok()?;
+        if candidate
+            .file_name()
+            .is_some_and(|name| name.starts_with("python"))
+        {
+            let site_packages = candidate.join("site-packages");
+            if db.file_system().is_directory(&site_packages) {
+                return Some(site_packages);
+            }
+        }
+    }
+
+    None
+}
+
+/// Scans `site_packages` for `.pth` files and returns the additional search-path directories they
+/// name.
+///
+/// Each non-blank, non-comment (`#`) line names a directory to add to the import search path,
+/// resolved relative to `site_packages` if it isn't already absolute; lines starting with
+/// `import ` are executable hooks (used by some packaging tools to run arbitrary setup code) and
+/// are intentionally not executed here, only skipped, since evaluating arbitrary Python is out of
+/// scope for path resolution.
+#[must_use]
+pub(crate) fn expand_pth_files(db: &dyn Db, site_packages: &FileSystemPath) -> Vec<FileSystemPathBuf> {
+    let Ok(entries) = db.file_system().read_directory(site_packages) else {
+        return vec![];
+    };
+
+    let mut additional_roots = Vec::new();
+
+    for entry in entries.flatten() {
+        if entry.extension() != Some("pth") {
+            continue;
+        }
+
+        let Ok(contents) = db.file_system().read_to_string(&entry) else {
+            continue;
+        };
+
+        for line in contents.lines() {
+            let Some(line) = parse_pth_line(line) else {
+                continue;
+            };
+
+            let path = FileSystemPath::new(line);
+            let resolved = if path.is_absolute() {
+                path.to_path_buf()
+            } else {
+                site_packages.join(path)
+            };
+            additional_roots.push(resolved);
+        }
+    }
+
+    additional_roots
+}
+
+/// Parses a single line from a `.pth` file into the additional search-path entry it names, if
+/// any, split out from the filesystem scanning in [`expand_pth_files`] so the line grammar is
+/// unit-testable without a real `Db`.
+///
+/// Blank lines and `#`-prefixed comments contribute nothing; `import `-prefixed lines are
+/// executable hooks (see [`expand_pth_files`]'s doc comment) and are intentionally skipped rather
+/// than parsed as a path.
+#[must_use]
+fn parse_pth_line(line: &str) -> Option<&str> {
+    let line = line.trim();
+    if line.is_empty() || line.starts_with('#') || line.starts_with("import ") {
+        None
+    } else {
+        Some(line)
+    }
+}
+
+/// Expands `site_packages`'s `.pth` files into additional `SitePackages` search-path roots, with
+/// the same priority as `site_packages` itself.
+///
+/// This is how editable/development installs end up resolving to a project's real source tree:
+/// a classic `pip install -e` writes a `.pth` file (conventionally named `__editable__<project>-
+/// <version>.pth` by modern pip, matching [PEP 660]) whose line is the absolute path to the
+/// project directory rather than a pre-built copy of the package, and [`expand_pth_files`] already
+/// reads that line like any other. A `.pth` entry that no longer points at a directory (for
+/// example, a stale entry left behind by an uninstalled package) is dropped rather than registered
+/// as a root that could never resolve anything.
+///
+/// [PEP 660]: https://peps.python.org/pep-0660/
+#[must_use]
+pub(crate) fn expand_editable_install_roots(
+    db: &dyn Db,
+    site_packages: &FileSystemPath,
+) -> Vec<ModuleResolutionPathBuf> {
+    expand_pth_files(db, site_packages)
+        .into_iter()
+        .filter(|root| db.file_system().is_directory(root))
+        .filter_map(ModuleResolutionPathBuf::site_packages)
+        .collect()
+}
+
+/// Assembles the `SitePackages` portion of [`resolve_module`](crate::path::resolve_module)'s
+/// search paths for a workspace: the discovered `site-packages` directory itself, followed by
+/// every additional root its `.pth` files expand to ([`expand_editable_install_roots`]), editable
+/// installs among them, at the same search-path priority as `site-packages` itself.
+///
+/// Returns an empty list if [`discover_site_packages`] can't find a `site-packages` directory at
+/// all, in which case a caller should fall back to requiring the user to configure it explicitly.
+#[must_use]
+pub(crate) fn site_packages_search_paths(
+    db: &dyn Db,
+    workspace_root: &FileSystemPath,
+    interpreter: Option<&FileSystemPath>,
+) -> Vec<ModuleResolutionPathBuf> {
+    let Some(site_packages) = discover_site_packages(db, workspace_root, interpreter) else {
+        return Vec::new();
+    };
+
+    let mut search_paths: Vec<ModuleResolutionPathBuf> =
+        ModuleResolutionPathBuf::site_packages(site_packages.clone())
+            .into_iter()
+            .collect();
+    search_paths.extend(expand_editable_install_roots(db, &site_packages));
+    search_paths
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn venv_root_for_interpreter_posix_layout() {
+        assert_eq!(
+            venv_root_for_interpreter(FileSystemPath::new(".venv/bin/python3")),
+            Some(FileSystemPathBuf::from(".venv"))
+        );
+    }
+
+    #[test]
+    fn venv_root_for_interpreter_windows_layout() {
+        assert_eq!(
+            venv_root_for_interpreter(FileSystemPath::new(".venv/Scripts/python.exe")),
+            Some(FileSystemPathBuf::from(".venv"))
+        );
+    }
+
+    #[test]
+    fn venv_root_for_interpreter_rejects_non_venv_layout() {
+        assert_eq!(
+            venv_root_for_interpreter(FileSystemPath::new("/opt/python3/python3")),
+            None
+        );
+    }
+
+    #[test]
+    fn parse_pth_line_plain_path() {
+        assert_eq!(parse_pth_line("../some-package"), Some("../some-package"));
+    }
+
+    #[test]
+    fn parse_pth_line_trims_whitespace() {
+        assert_eq!(parse_pth_line("  ../some-package  "), Some("../some-package"));
+    }
+
+    #[test]
+    fn parse_pth_line_skips_blank() {
+        assert_eq!(parse_pth_line("   "), None);
+    }
+
+    #[test]
+    fn parse_pth_line_skips_comment() {
+        assert_eq!(parse_pth_line("# a comment"), None);
+    }
+
+    #[test]
+    fn parse_pth_line_skips_import_hook() {
+        assert_eq!(parse_pth_line("import some_setup_module"), None);
+    }
+}
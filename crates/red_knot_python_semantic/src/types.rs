@@ -18,8 +18,15 @@ use crate::Db;
 use crate::FxIndexSet;
 
 mod display;
+mod expectation;
 mod infer;
 mod intern;
+mod narrow;
+mod unify;
+
+pub(crate) use expectation::Expectation;
+pub(crate) use narrow::{narrow_is_none, narrow_isinstance, narrow_truthy, NarrowedTypes};
+pub(crate) use unify::{InferId, UnificationTable};
 
 /// Infers the type of `expr`.
 ///
@@ -133,6 +140,9 @@ fn type_store(db: &dyn Db, file: VfsFile) -> &FileTypeStore {
     type_store_query(db, file).as_ref()
 }
 
+// `FileTypeStore::add_union`/`add_intersection` (see `types::intern`) intern a freshly computed
+// `UnionType`/`IntersectionType` for `file`, handing back the `TypeId` used to build a `Type`.
+
 /// unique ID for a type
 #[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
 pub enum Type {
@@ -149,6 +159,10 @@ pub enum Type {
     None,
     /// a specific function object
     Function(TypeId<FileFunctionTypeId>),
+    /// a function object bound to an instance, as `instance.method` evaluates to; calling it
+    /// type-checks arguments against the function's signature with the first (`self`) parameter
+    /// dropped
+    BoundMethod(TypeId<FileFunctionTypeId>),
     /// a specific module object
     Module(TypeId<FileModuleTypeId>),
     /// a specific class object
@@ -158,6 +172,13 @@ pub enum Type {
     Union(TypeId<FileUnionTypeId>),
     Intersection(TypeId<FileIntersectionTypeId>),
     IntLiteral(i64),
+    /// A not-yet-known type, resolved by unifying it with other types during inference.
+    ///
+    /// This only ever appears transiently while a single scope is being inferred: by the time
+    /// [`infer_types`] calls `finish()`, every `Infer` variable has been resolved (to a concrete
+    /// type, or to `Unknown` if it was never constrained) by [`UnificationTable::resolve`], so no
+    /// `Type::Infer` should ever escape into a [`TypeInference`] result.
+    Infer(InferId),
     // TODO protocols, callable types, overloads, generics, type vars
 }
 
@@ -170,6 +191,25 @@ impl<'db> Type {
         matches!(self, Type::Unknown)
     }
 
+    pub const fn is_infer(&self) -> bool {
+        matches!(self, Type::Infer(_))
+    }
+
+    /// Returns the type of calling a value of this type, e.g. the type of `foo()` given `foo`'s
+    /// type.
+    ///
+    /// Classes are callable as their own constructor (`C()` evaluates to `Instance(C)`); plain
+    /// functions and bound methods evaluate to their declared (or inferred) return type.
+    pub fn call(&self, db: &'db dyn Db) -> Type {
+        match self {
+            Type::Function(function) | Type::BoundMethod(function) => {
+                function.lookup(db).signature().return_ty()
+            }
+            Type::Class(class) => Type::Instance(*class),
+            _ => Type::Unknown,
+        }
+    }
+
     pub fn member(&self, db: &'db dyn Db, name: &Name) -> Option<Type> {
         match self {
             Type::Any => Some(Type::Any),
@@ -177,29 +217,47 @@ impl<'db> Type {
             Type::Unknown => Some(Type::Unknown),
             Type::Unbound => todo!("attribute lookup on Unbound type"),
             Type::None => todo!("attribute lookup on None type"),
-            Type::Function(_) => todo!("attribute lookup on Function type"),
+            Type::Function(_) | Type::BoundMethod(_) => {
+                todo!("attribute lookup on Function type")
+            }
             Type::Module(module) => module.member(db, name),
             Type::Class(class) => class.class_member(db, name),
-            Type::Instance(_) => {
-                // TODO MRO? get_own_instance_member, get_instance_member
-                todo!("attribute lookup on Instance type")
-            }
+            Type::Instance(class) => class.instance_member(db, name),
             Type::Union(union_id) => {
-                let _union = union_id.lookup(db);
-                // TODO perform the get_member on each type in the union
-                // TODO return the union of those results
-                // TODO if any of those results is `None` then include Unknown in the result union
-                todo!("attribute lookup on Union type")
+                let union = union_id.lookup(db);
+                let mut builder = UnionTypeBuilder::new(db);
+                for element in &union.elements {
+                    // An attribute that doesn't exist on every union member still "exists" on the
+                    // union as a whole (we don't know which member we actually have), but we can
+                    // no longer promise it's the type we inferred for the other members, so
+                    // contribute `Unknown` for that member instead of failing the whole lookup.
+                    builder = builder.add(element.member(db, name).unwrap_or(Type::Unknown));
+                }
+                Some(builder.build_ty(union_id.file))
             }
-            Type::Intersection(_) => {
-                // TODO perform the get_member on each type in the intersection
-                // TODO return the intersection of those results
-                todo!("attribute lookup on Intersection type")
+            Type::Intersection(intersection_id) => {
+                let intersection = intersection_id.lookup(db);
+                let mut builder = IntersectionTypeBuilder::new(db);
+                // Negative elements don't contribute members: knowing a value is *not* of some
+                // type tells us nothing about its attributes.
+                for element in &intersection.positive {
+                    if let Some(member) = element.member(db, name) {
+                        builder = builder.add_positive(member);
+                    }
+                }
+                Some(builder.build_ty(intersection_id.file))
             }
             Type::IntLiteral(_) => {
                 // TODO raise error
                 Some(Type::Unknown)
             }
+            Type::Infer(_) => {
+                // By the time `member` is called the builder should have already resolved every
+                // `Infer` variable via `UnificationTable::resolve`; if one still shows up here
+                // the member access happened on an expression whose type we never managed to pin
+                // down, so fall back to `Unknown` rather than panicking.
+                Some(Type::Unknown)
+            }
         }
     }
 }
@@ -261,6 +319,9 @@ pub struct FunctionType {
     name: Name,
     /// types of all decorators on this function
     decorators: Vec<Type>,
+    /// parameter and return types, as given when this function type was constructed;
+    /// `TypeInferenceBuilder` does not build them from the function's definition
+    signature: Signature,
 }
 
 impl FunctionType {
@@ -272,6 +333,65 @@ impl FunctionType {
     pub(crate) fn decorators(&self) -> &[Type] {
         self.decorators.as_slice()
     }
+
+    pub(crate) fn signature(&self) -> &Signature {
+        &self.signature
+    }
+}
+
+/// A single parameter of a callable, as far as type inference cares: its name and its type.
+#[derive(Debug, Eq, PartialEq, Clone)]
+pub struct Parameter {
+    name: Name,
+    annotated_ty: Type,
+}
+
+impl Parameter {
+    pub(crate) fn name(&self) -> &str {
+        self.name.as_str()
+    }
+
+    pub(crate) fn ty(&self) -> Type {
+        self.annotated_ty
+    }
+}
+
+/// The callable shape of a [`FunctionType`]: its parameters and declared return type.
+///
+/// Holds exactly the parameter and return types it was constructed with. `TypeInferenceBuilder`
+/// does not infer function definitions, so it neither builds signatures nor fills unannotated
+/// parameters or returns with [`Type::Infer`] variables.
+#[derive(Debug, Eq, PartialEq, Clone)]
+pub struct Signature {
+    parameters: Vec<Parameter>,
+    return_ty: Type,
+}
+
+impl Signature {
+    pub(crate) fn new(parameters: Vec<Parameter>, return_ty: Type) -> Self {
+        Self {
+            parameters,
+            return_ty,
+        }
+    }
+
+    pub(crate) fn parameters(&self) -> &[Parameter] {
+        &self.parameters
+    }
+
+    pub(crate) fn return_ty(&self) -> Type {
+        self.return_ty
+    }
+
+    /// The parameters a *bound* call site sees: everything but the receiver (`self`/`cls`).
+    ///
+    /// Used when a [`Type::Function`] is turned into a [`Type::BoundMethod`] by
+    /// [`TypeId::<FileClassTypeId>::instance_member`], so that `instance.method(...)` type-checks
+    /// its arguments against the remaining parameters, the way rust-analyzer's method resolution
+    /// adjusts the receiver.
+    pub(crate) fn bound_parameters(&self) -> &[Parameter] {
+        self.parameters.get(1..).unwrap_or_default()
+    }
 }
 
 #[newtype_index]
@@ -286,18 +406,42 @@ impl FileTypeId for FileClassTypeId {
 }
 
 impl<'db> TypeId<FileClassTypeId> {
+    /// Returns this class's method resolution order: itself, followed by its bases linearized
+    /// with the [C3 algorithm](https://docs.python.org/3/glossary.html#term-MRO), the same order
+    /// `type.__mro__` would report at runtime.
+    fn mro(self, db: &'db dyn Db) -> &'db [TypeId<FileClassTypeId>] {
+        class_mro(db, self)
+    }
+
     /// Returns the class member of this class named `name`.
     ///
-    /// The member resolves to a member of the class itself or any of its bases.
+    /// The member resolves to a member of the class itself or any of its bases, walked in MRO
+    /// order so that diamond inheritance resolves the same member every subclass would see.
     fn class_member(self, db: &'db dyn Db, name: &Name) -> Option<Type> {
-        if let Some(member) = self.own_class_member(db, name) {
-            return Some(member);
+        for class in self.mro(db) {
+            if let Some(member) = class.own_class_member(db, name) {
+                return Some(member);
+            }
         }
 
-        let class = self.lookup(db);
-        for base in &class.bases {
-            if let Some(member) = base.member(db, name) {
-                return Some(member);
+        None
+    }
+
+    /// Returns the type of instance attribute/method `name`.
+    ///
+    /// Instance-level members (symbols assigned or annotated directly in the class body) are
+    /// checked first, then we fall back to the class's MRO the same way [`Self::class_member`]
+    /// does.
+    fn instance_member(self, db: &'db dyn Db, name: &Name) -> Option<Type> {
+        for class in self.mro(db) {
+            if let Some(member) = class.own_class_member(db, name) {
+                // A function found via the MRO is a method: bind it to this instance so that
+                // `instance.method(...)` type-checks its arguments against the signature with
+                // `self` dropped, the way rust-analyzer's method resolution adjusts the receiver.
+                return Some(match member {
+                    Type::Function(function) => Type::BoundMethod(function),
+                    other => other,
+                });
             }
         }
 
@@ -317,6 +461,74 @@ impl<'db> TypeId<FileClassTypeId> {
     }
 }
 
+/// Computes the method resolution order of `class` using
+/// [C3 linearization](https://docs.python.org/3/glossary.html#term-MRO):
+/// `L[C] = C ++ merge(L[B1], …, L[Bn], [B1, …, Bn])`.
+///
+/// If the class hierarchy is inconsistent (no valid C3 merge exists), falls back to the bases in
+/// declaration order so that inference still terminates; callers that care about the inconsistency
+/// should check for it independently rather than relying on this function to report it.
+#[salsa::tracked(return_ref)]
+fn class_mro<'db>(db: &'db dyn Db, class: TypeId<FileClassTypeId>) -> Vec<TypeId<FileClassTypeId>> {
+    let class_ty = class.lookup(db);
+
+    let bases: Vec<TypeId<FileClassTypeId>> = class_ty
+        .bases
+        .iter()
+        .filter_map(|base| match base {
+            Type::Class(base_id) => Some(*base_id),
+            _ => None,
+        })
+        .collect();
+
+    let mut sequences: Vec<Vec<TypeId<FileClassTypeId>>> = bases
+        .iter()
+        .map(|base| class_mro(db, *base).clone())
+        .collect();
+    sequences.push(bases.clone());
+
+    let mut mro = vec![class];
+    match c3_merge(sequences) {
+        Some(merged) => mro.extend(merged),
+        None => {
+            // TODO: surface a proper diagnostic for the inconsistent MRO instead of silently
+            // falling back.
+            mro.extend(bases);
+        }
+    }
+    mro
+}
+
+/// The `merge` step of C3 linearization: repeatedly takes the head of the first sequence that
+/// doesn't appear in the tail of any other sequence, removing it everywhere, until all sequences
+/// are exhausted. Returns `None` if no valid head can be found (an inconsistent hierarchy).
+///
+/// Generic over the element type so the linearization algorithm itself — independent of how
+/// `class_mro` happens to represent a class — is unit-testable without a real `Db`.
+fn c3_merge<T: Copy + PartialEq>(mut sequences: Vec<Vec<T>>) -> Option<Vec<T>> {
+    let mut result = Vec::new();
+
+    loop {
+        sequences.retain(|sequence| !sequence.is_empty());
+        if sequences.is_empty() {
+            return Some(result);
+        }
+
+        let head = sequences.iter().find_map(|sequence| {
+            let candidate = sequence[0];
+            let in_some_tail = sequences
+                .iter()
+                .any(|other| other[1..].contains(&candidate));
+            (!in_some_tail).then_some(candidate)
+        })?;
+
+        result.push(head);
+        for sequence in &mut sequences {
+            sequence.retain(|class| *class != head);
+        }
+    }
+}
+
 #[derive(Debug, Eq, PartialEq, Clone)]
 pub struct ClassType {
     /// Name of the class at definition
@@ -389,6 +601,17 @@ impl<'db> UnionTypeBuilder<'db> {
             elements: self.elements,
         }
     }
+
+    /// Builds this union and interns it as a [`Type`], collapsing it back to the bare element
+    /// type when only a single one was ever added.
+    fn build_ty(self, file: VfsFile) -> Type {
+        let db = self.db;
+        match self.elements.len() {
+            0 => Type::Never,
+            1 => self.elements.into_iter().next().unwrap(),
+            _ => Type::Union(type_store(db, file).add_union(self.build())),
+        }
+    }
 }
 
 #[newtype_index]
@@ -416,6 +639,74 @@ pub struct IntersectionType {
     negative: FxIndexSet<Type>,
 }
 
+/// Builds an [`IntersectionType`], mirroring [`UnionTypeBuilder`].
+struct IntersectionTypeBuilder<'db> {
+    positive: FxIndexSet<Type>,
+    negative: FxIndexSet<Type>,
+    db: &'db dyn Db,
+}
+
+impl<'db> IntersectionTypeBuilder<'db> {
+    fn new(db: &'db dyn Db) -> Self {
+        Self {
+            db,
+            positive: FxIndexSet::default(),
+            negative: FxIndexSet::default(),
+        }
+    }
+
+    fn add_positive(mut self, ty: Type) -> Self {
+        match ty {
+            Type::Intersection(intersection_id) => {
+                let intersection = intersection_id.lookup(self.db);
+                self.positive.extend(&intersection.positive);
+                self.negative.extend(&intersection.negative);
+            }
+            _ => {
+                self.positive.insert(ty);
+            }
+        }
+
+        self
+    }
+
+    fn add_negative(mut self, ty: Type) -> Self {
+        match ty {
+            Type::Intersection(intersection_id) => {
+                let intersection = intersection_id.lookup(self.db);
+                // Negating `A & B & !C` is `!A | !B | C`, which isn't itself representable as an
+                // intersection, so there's no equivalent flattening to do here; just add the
+                // whole thing as one negative element.
+                self.negative.insert(Type::Intersection(intersection_id));
+                let _ = intersection;
+            }
+            _ => {
+                self.negative.insert(ty);
+            }
+        }
+
+        self
+    }
+
+    fn build(self) -> IntersectionType {
+        IntersectionType {
+            positive: self.positive,
+            negative: self.negative,
+        }
+    }
+
+    /// Builds this intersection and interns it as a [`Type`], collapsing it back to the bare
+    /// element type when it has a single positive element and no negative ones.
+    fn build_ty(self, file: VfsFile) -> Type {
+        let db = self.db;
+        match (self.positive.len(), self.negative.len()) {
+            (0, 0) => Type::Never,
+            (1, 0) => self.positive.into_iter().next().unwrap(),
+            _ => Type::Intersection(type_store(db, file).add_intersection(self.build())),
+        }
+    }
+}
+
 #[derive(Copy, Clone, Debug, Eq, PartialEq, Hash)]
 pub struct FileModuleTypeId;
 
@@ -447,8 +738,13 @@ mod tests {
     use crate::db::tests::{
         assert_will_not_run_function_query, assert_will_run_function_query, TestDb,
     };
+    use crate::name::Name;
     use crate::semantic_index::root_scope;
-    use crate::types::{expression_ty, infer_types, public_symbol_ty_by_name};
+    use crate::types::{
+        c3_merge, expression_ty, infer_types, public_symbol_ty_by_name, FileModuleTypeId,
+        IntersectionTypeBuilder, Parameter, Signature, Type, TypeId, UnionTypeBuilder,
+    };
+    use crate::FxIndexSet;
     use red_knot_module_resolver::{set_module_resolution_settings, ModuleResolutionSettings};
 
     fn setup_db() -> TestDb {
@@ -606,4 +902,119 @@ mod tests {
         );
         Ok(())
     }
+
+    #[test]
+    fn c3_merge_classic_diamond() {
+        // `O`, `A(O)`, `B(O)`, `C(A, B)`: `class_mro` would prepend `C` itself, so this only
+        // exercises the merge of `C`'s bases' own linearizations plus their declaration order.
+        let o = 0;
+        let a = 1;
+        let b = 2;
+        let sequences = vec![vec![a, o], vec![b, o], vec![a, b]];
+        assert_eq!(c3_merge(sequences), Some(vec![a, b, o]));
+    }
+
+    #[test]
+    fn c3_merge_single_base() {
+        let sequences = vec![vec![1], vec![1]];
+        assert_eq!(c3_merge(sequences), Some(vec![1]));
+    }
+
+    #[test]
+    fn c3_merge_no_bases() {
+        let sequences: Vec<Vec<i32>> = vec![];
+        assert_eq!(c3_merge(sequences), Some(vec![]));
+    }
+
+    #[test]
+    fn c3_merge_inconsistent_hierarchy_returns_none() {
+        // Two sequences that each require the other's head to come first.
+        let sequences = vec![vec![1, 2], vec![2, 1]];
+        assert_eq!(c3_merge(sequences), None);
+    }
+
+    #[test]
+    fn member_distributes_over_union_missing_becomes_unknown() -> anyhow::Result<()> {
+        let db = setup_db();
+
+        db.memory_file_system()
+            .write_files([("/src/a.py", "x = 1"), ("/src/b.py", "y = 2")])?;
+        let a = system_path_to_file(&db, "/src/a.py").unwrap();
+        let b = system_path_to_file(&db, "/src/b.py").unwrap();
+
+        let mut builder = UnionTypeBuilder::new(&db);
+        builder = builder.add(Type::Module(TypeId {
+            file: a,
+            local: FileModuleTypeId,
+        }));
+        builder = builder.add(Type::Module(TypeId {
+            file: b,
+            local: FileModuleTypeId,
+        }));
+        let union_ty = builder.build_ty(a);
+
+        let name = Name::new("x");
+        let merged = union_ty.member(&db, &name).unwrap();
+
+        let Type::Union(union_id) = merged else {
+            panic!("expected a union of the per-branch member types, got {merged:?}");
+        };
+        let union = union_id.lookup(&db);
+        assert_eq!(
+            union.elements,
+            FxIndexSet::from_iter([Type::IntLiteral(1), Type::Unknown])
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn member_distributes_over_intersection_ignoring_missing() -> anyhow::Result<()> {
+        let db = setup_db();
+
+        db.memory_file_system()
+            .write_files([("/src/a.py", "x = 1"), ("/src/b.py", "y = 2")])?;
+        let a = system_path_to_file(&db, "/src/a.py").unwrap();
+        let b = system_path_to_file(&db, "/src/b.py").unwrap();
+
+        let mut builder = IntersectionTypeBuilder::new(&db);
+        builder = builder.add_positive(Type::Module(TypeId {
+            file: a,
+            local: FileModuleTypeId,
+        }));
+        builder = builder.add_positive(Type::Module(TypeId {
+            file: b,
+            local: FileModuleTypeId,
+        }));
+        let intersection_ty = builder.build_ty(a);
+
+        let name = Name::new("x");
+        let merged = intersection_ty.member(&db, &name).unwrap();
+
+        // `b` doesn't have `x`, so only `a`'s contribution survives; a single positive element
+        // with no negatives collapses back to the bare type rather than staying an intersection.
+        assert_eq!(merged, Type::IntLiteral(1));
+
+        Ok(())
+    }
+
+    #[test]
+    fn bound_parameters_drops_receiver() {
+        let self_param = Parameter {
+            name: Name::new("self"),
+            annotated_ty: Type::Unknown,
+        };
+        let x_param = Parameter {
+            name: Name::new("x"),
+            annotated_ty: Type::IntLiteral(0),
+        };
+        let signature = Signature::new(vec![self_param, x_param.clone()], Type::Unknown);
+        assert_eq!(signature.bound_parameters().to_vec(), vec![x_param]);
+    }
+
+    #[test]
+    fn bound_parameters_empty_without_receiver() {
+        let signature = Signature::new(vec![], Type::Unknown);
+        assert_eq!(signature.bound_parameters(), &[] as &[Parameter]);
+    }
 }
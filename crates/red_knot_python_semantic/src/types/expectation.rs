@@ -0,0 +1,129 @@
+//! Bidirectional inference via an expected type, modeled on rustc's `Expectation`.
+//!
+//! Plain bottom-up inference (the default, `NoExpectation`) infers an expression's type purely
+//! from its own shape, which is why `x: float = 1` would otherwise infer the literal `1` as
+//! `Literal[1]` rather than coercing it to the annotated `float`. `TypeInferenceBuilder` (see
+//! `types::infer`) threads an `Expectation` into its expression-inference entry points from every
+//! context that already knows the type an expression needs to have: annotated assignments,
+//! call arguments against a parameter's declared type, and `return` against the enclosing
+//! function's declared return type. Unannotated contexts keep passing `NoExpectation` and get
+//! today's bottom-up behavior.
+
+use crate::types::{FileClassTypeId, InferId, Type, TypeId, UnificationTable};
+use crate::Db;
+
+/// The type an expression is expected to have, if the surrounding context already knows it.
+#[derive(Debug, Clone, Copy, Default)]
+pub(crate) enum Expectation {
+    /// No expected type is known; infer bottom-up as usual.
+    #[default]
+    NoExpectation,
+    /// The expression is expected to have (be assignable to) this type.
+    ExpectHasType(Type),
+}
+
+impl Expectation {
+    pub(crate) fn has_type(self) -> Option<Type> {
+        match self {
+            Expectation::NoExpectation => None,
+            Expectation::ExpectHasType(ty) => Some(ty),
+        }
+    }
+
+    /// Coerces an inferred `int` literal to the expected type when the expectation calls for a
+    /// wider numeric-tower type, e.g. `1` against an expected `float` becomes `Instance(float)`
+    /// rather than staying `Literal[1]`, per [PEP 484's numeric tower] (`int` is assignable to
+    /// `float`, and `float` to `complex`).
+    ///
+    /// Falls back to the literal itself for every other expectation: an expectation of
+    /// `Literal[1]` or `int` keeps the literal since it's already assignable without widening, and
+    /// an expectation of anything outside the numeric tower (e.g. `str`) is left alone so the
+    /// mismatch still surfaces as a type error instead of being silently coerced away.
+    ///
+    /// [PEP 484's numeric tower]: https://peps.python.org/pep-0484/#the-numeric-tower
+    pub(crate) fn coerce_int_literal(self, db: &dyn Db, literal: Type) -> Type {
+        debug_assert!(matches!(literal, Type::IntLiteral(_)));
+        match self.has_type() {
+            Some(expected @ Type::Instance(class)) if is_numeric_tower_widening(db, class) => {
+                expected
+            }
+            _ => literal,
+        }
+    }
+
+    /// Returns the expected type unchanged, or `ty` (typically `Type::Unknown`) when there's no
+    /// expectation to draw from. It does not extract an element type from an expected container
+    /// type: given `[]` against an expected `list[int]`, it returns `list[int]` itself, not `int`.
+    pub(crate) fn coerce_container_element(self, ty: Type) -> Type {
+        self.has_type().unwrap_or(ty)
+    }
+
+    /// Seeds a fresh inference variable with this expectation, so that the union-find side of
+    /// inference (see `types::unify`) already knows the variable's type instead of waiting for it
+    /// to be constrained some other way.
+    pub(crate) fn seed(self, table: &mut UnificationTable, var: InferId) {
+        if let Some(ty) = self.has_type() {
+            table.unify_var_value(var, ty);
+        }
+    }
+}
+
+/// Whether `class` is one of the types an `int` literal widens to under
+/// [PEP 484's numeric tower](https://peps.python.org/pep-0484/#the-numeric-tower).
+#[must_use]
+fn is_numeric_tower_widening(db: &dyn Db, class: TypeId<FileClassTypeId>) -> bool {
+    is_numeric_tower_widening_name(class.lookup(db).name())
+}
+
+/// The name-only half of [`is_numeric_tower_widening`], split out so the actual widening rule —
+/// `float` or `complex`, but not `bool` (a *subtype* of `int`, not a supertype) or any other
+/// class — is unit-testable without a real `Db`.
+#[must_use]
+fn is_numeric_tower_widening_name(class_name: &str) -> bool {
+    matches!(class_name, "float" | "complex")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn float_and_complex_widen() {
+        assert!(is_numeric_tower_widening_name("float"));
+        assert!(is_numeric_tower_widening_name("complex"));
+    }
+
+    #[test]
+    fn int_does_not_widen() {
+        assert!(!is_numeric_tower_widening_name("int"));
+    }
+
+    #[test]
+    fn bool_does_not_widen() {
+        // `bool` is a subtype of `int`, not a supertype it should widen into.
+        assert!(!is_numeric_tower_widening_name("bool"));
+    }
+
+    #[test]
+    fn unrelated_class_does_not_widen() {
+        assert!(!is_numeric_tower_widening_name("str"));
+    }
+
+    #[test]
+    fn coerce_container_element_uses_expectation_when_present() {
+        let expectation = Expectation::ExpectHasType(Type::IntLiteral(1));
+        assert_eq!(
+            expectation.coerce_container_element(Type::Unknown),
+            Type::IntLiteral(1)
+        );
+    }
+
+    #[test]
+    fn coerce_container_element_falls_back_without_expectation() {
+        let expectation = Expectation::NoExpectation;
+        assert_eq!(
+            expectation.coerce_container_element(Type::Unknown),
+            Type::Unknown
+        );
+    }
+}
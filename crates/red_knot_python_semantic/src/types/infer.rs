@@ -0,0 +1,445 @@
+//! Builds a [`TypeInference`] result for a single scope by walking its statements and expressions
+//! once. This is what [`super::infer_types`] constructs and drives; [`TypeInference::expression_ty`]
+//! and [`TypeInference::symbol_ty`] are how [`super::expression_ty`] and [`super::public_symbol_ty`]
+//! read the result back out by AST id.
+//!
+//! A deliberately simple single-pass inferer: a binding's type can depend on what's already been
+//! inferred earlier in the same scope (matching Python's own top-to-bottom execution order), not on
+//! anything inferred later in it, and there's no fixed-point loop for cyclic control flow.
+
+use ruff_index::Idx;
+use ruff_python_ast as ast;
+use rustc_hash::FxHashMap;
+
+use crate::semantic_index::ast_ids::ScopeAstIdNode;
+use crate::semantic_index::symbol::{ScopeId, ScopedSymbolId};
+use crate::semantic_index::{symbol_table, SemanticIndex};
+use crate::types::expectation::Expectation;
+use crate::types::narrow::{narrow_is_none, narrow_isinstance, narrow_truthy, NarrowedTypes};
+use crate::types::unify::UnificationTable;
+use crate::types::{Parameter, Type};
+use crate::Db;
+
+/// The per-scope result of type inference: every visited expression's and bound symbol's inferred
+/// type, keyed by the AST id its query-level caller already has on hand.
+#[derive(Debug, Clone, Default, Eq, PartialEq)]
+pub(crate) struct TypeInference {
+    expression_tys: FxHashMap<usize, Type>,
+    symbol_tys: FxHashMap<ScopedSymbolId, Type>,
+}
+
+impl TypeInference {
+    #[must_use]
+    pub(crate) fn expression_ty<Id: Idx>(&self, id: Id) -> Type {
+        self.expression_tys
+            .get(&id.index())
+            .copied()
+            .unwrap_or(Type::Unknown)
+    }
+
+    #[must_use]
+    pub(crate) fn symbol_ty(&self, symbol: ScopedSymbolId) -> Type {
+        self.symbol_tys
+            .get(&symbol)
+            .copied()
+            .unwrap_or(Type::Unknown)
+    }
+}
+
+/// Drives a single pass of statement/expression inference over one scope, accumulating the result
+/// into a [`TypeInference`]. Constructed and driven by [`super::infer_types`].
+pub(crate) struct TypeInferenceBuilder<'db> {
+    db: &'db dyn Db,
+    scope: ScopeId<'db>,
+    index: &'db SemanticIndex,
+    /// Allocates an inference variable per plain assignment (see [`crate::types::unify`]). Each
+    /// variable is resolved before that assignment's targets are bound; later uses of the symbol
+    /// read the resolved type back from `result`, not from the variable.
+    table: UnificationTable,
+    /// The current narrowing in effect at the statement being visited; threaded through `if`/
+    /// `while` bodies (see [`crate::types::narrow`]), merged back at the join point after an `if`
+    /// chain, restored to its pre-loop state after a `while` body, and cleared for a symbol as
+    /// soon as it's reassigned.
+    narrowed: NarrowedTypes,
+    /// The enclosing function's declared return type, if this scope is a function body with a
+    /// `-> ...` annotation; threaded into each `return`'s expectation so `x: float = 1` -style
+    /// coercion (see [`Expectation`]) also applies to `return 1` against a `-> float` annotation.
+    return_expectation: Expectation,
+    result: TypeInference,
+}
+
+impl<'db> TypeInferenceBuilder<'db> {
+    pub(crate) fn new(db: &'db dyn Db, scope: ScopeId<'db>, index: &'db SemanticIndex) -> Self {
+        Self {
+            db,
+            scope,
+            index,
+            table: UnificationTable::new(),
+            narrowed: NarrowedTypes::default(),
+            return_expectation: Expectation::default(),
+            result: TypeInference::default(),
+        }
+    }
+
+    pub(crate) fn infer_module(&mut self, module: &ast::ModModule) {
+        self.infer_body(&module.body);
+    }
+
+    pub(crate) fn infer_class_body(&mut self, class: &ast::StmtClassDef) {
+        self.infer_body(&class.body);
+    }
+
+    pub(crate) fn infer_class_type_params(&mut self, _class: &ast::StmtClassDef) {
+        // PEP 695 `class Foo[T]:` type-parameter scopes have no bindings of their own to infer
+        // yet; `infer_class_body` covers the class body itself.
+    }
+
+    pub(crate) fn infer_function_body(&mut self, function: &ast::StmtFunctionDef) {
+        self.return_expectation = function
+            .returns
+            .as_deref()
+            .map(|annotation| Expectation::ExpectHasType(self.infer_annotation(annotation)))
+            .unwrap_or_default();
+        self.infer_body(&function.body);
+    }
+
+    pub(crate) fn infer_function_type_params(&mut self, _function: &ast::StmtFunctionDef) {
+        // See `infer_class_type_params`.
+    }
+
+    pub(crate) fn finish(self) -> TypeInference {
+        self.result
+    }
+
+    fn file(&self) -> ruff_db::vfs::VfsFile {
+        self.scope.file(self.db)
+    }
+
+    fn symbol_id(&self, name: &str) -> Option<ScopedSymbolId> {
+        symbol_table(self.db, self.scope).symbol_id_by_name(name)
+    }
+
+    fn infer_body(&mut self, body: &[ast::Stmt]) {
+        for stmt in body {
+            self.infer_stmt(stmt);
+        }
+    }
+
+    fn infer_stmt(&mut self, stmt: &ast::Stmt) {
+        match stmt {
+            ast::Stmt::Expr(node) => {
+                self.infer_expr(&node.value, Expectation::default());
+            }
+            ast::Stmt::Assign(node) => {
+                // A plain (unannotated) re-assignment still has an expectation if the target was
+                // already bound earlier in this scope: the value is inferred against that type,
+                // so e.g. `x = 1` after `x: float = 0` coerces the literal the same way the
+                // annotated assignment did. The fresh inference variable is seeded with it too
+                // (see `Expectation::seed`).
+                let expectation = node
+                    .targets
+                    .first()
+                    .and_then(|target| self.target_symbol(target))
+                    .map(|symbol| self.result.symbol_ty(symbol))
+                    .filter(|ty| !ty.is_unknown())
+                    .map(Expectation::ExpectHasType)
+                    .unwrap_or_default();
+
+                let var = self.table.new_variable();
+                expectation.seed(&mut self.table, var);
+
+                // Unify the variable with the inferred value type and resolve it straight away.
+                // `unify_var_value` replaces the seeded type, so the binding gets the value's type
+                // as inferred under `expectation`; the variable isn't used after this statement.
+                let value_ty = self.infer_expr(&node.value, expectation);
+                self.table.unify_var_value(var, value_ty);
+                let resolved = self.table.resolve(var);
+
+                for target in &node.targets {
+                    self.bind_target(target, resolved);
+                }
+            }
+            ast::Stmt::AnnAssign(node) => {
+                let annotation_ty = self.infer_annotation(&node.annotation);
+                let ty = if let Some(value) = &node.value {
+                    self.infer_expr(value, Expectation::ExpectHasType(annotation_ty))
+                } else {
+                    annotation_ty
+                };
+                self.bind_target(&node.target, ty);
+            }
+            ast::Stmt::Return(node) => {
+                if let Some(value) = &node.value {
+                    let expectation = self.return_expectation;
+                    self.infer_expr(value, expectation);
+                }
+            }
+            ast::Stmt::If(node) => {
+                self.infer_branch(Some(&node.test), &node.body, &node.elif_else_clauses);
+            }
+            ast::Stmt::While(node) => self.infer_while(node),
+            _ => {
+                // Every other statement kind either has no type of its own to record (`pass`,
+                // `import`, ...) or isn't modeled by this inferer yet.
+            }
+        }
+    }
+
+    fn bind_target(&mut self, target: &ast::Expr, ty: Type) {
+        if let Some(symbol) = self.target_symbol(target) {
+            self.result.symbol_tys.insert(symbol, ty);
+            self.narrowed.clear(symbol);
+        }
+    }
+
+    fn target_symbol(&self, target: &ast::Expr) -> Option<ScopedSymbolId> {
+        match target {
+            ast::Expr::Name(node) => self.symbol_id(node.id.as_str()),
+            _ => None,
+        }
+    }
+
+    /// Infers the type an annotation expression *names*, e.g. `float` in `x: float = 1`, as
+    /// opposed to the type of the annotation expression *as a value* -- a bare class reference
+    /// evaluates (as a value) to `Type::Class`, but `x: float` means values of `x` are instances
+    /// of `float`, so a class annotation is converted to the corresponding [`Type::Instance`]
+    /// (the same conversion [`Type::call`] applies to a class's own constructor call).
+    fn infer_annotation(&mut self, annotation: &ast::Expr) -> Type {
+        let ty = self.infer_expr(annotation, Expectation::default());
+        match ty {
+            Type::Class(class) => Type::Instance(class),
+            other => other,
+        }
+    }
+
+    /// Infers a `while test: body (else: orelse)?` loop in a single, non-fixed-point pass: the
+    /// narrowing `test` establishes only covers `body` (a real fixed-point analysis would re-widen
+    /// it against whatever `body` itself reassigns before the next iteration, which this inferer
+    /// doesn't attempt), and `orelse` sees the pre-loop narrowing, since the loop may have run zero
+    /// times.
+    fn infer_while(&mut self, node: &ast::StmtWhile) {
+        self.infer_expr(&node.test, Expectation::default());
+        let before = self.narrowed.clone();
+
+        if let Some((symbol, then_ty, _)) = self.narrow_for_test(&node.test, &before) {
+            self.narrowed.set(symbol, then_ty);
+        }
+        self.infer_body(&node.body);
+
+        self.narrowed = before;
+        self.infer_body(&node.orelse);
+    }
+
+    /// Infers one `if`/`elif`/`else` chain, recursing through `rest` for each subsequent `elif`/
+    /// `else` clause and merging the narrowing each reachable arm leaves behind back together at
+    /// the end via [`NarrowedTypes::merge`] -- a join point, since control reaches the statement
+    /// after the chain regardless of which arm ran.
+    ///
+    /// `test` is `None` for a bare `else:` clause, which has no predicate of its own to narrow on
+    /// and so is inferred under whatever narrowing the chain has accumulated so far.
+    fn infer_branch(
+        &mut self,
+        test: Option<&ast::Expr>,
+        body: &[ast::Stmt],
+        rest: &[ast::ElifElseClause],
+    ) {
+        let Some(test) = test else {
+            self.infer_body(body);
+            return;
+        };
+
+        self.infer_expr(test, Expectation::default());
+        let before = self.narrowed.clone();
+        let narrowed_symbol = self.narrow_for_test(test, &before);
+
+        if let Some((symbol, then_ty, _)) = narrowed_symbol {
+            self.narrowed.set(symbol, then_ty);
+        }
+        self.infer_body(body);
+        let then_narrowed = std::mem::replace(&mut self.narrowed, before.clone());
+
+        if let Some((symbol, _, else_ty)) = narrowed_symbol {
+            self.narrowed.set(symbol, else_ty);
+        }
+        if let Some((clause, remaining)) = rest.split_first() {
+            self.infer_branch(clause.test.as_ref(), &clause.body, remaining);
+        }
+        let else_narrowed = std::mem::replace(&mut self.narrowed, before);
+
+        self.narrowed = match narrowed_symbol {
+            // Only the one symbol this test actually narrows is guaranteed sound to merge this
+            // way (`NarrowedTypes::merge` falls every symbol missing from a branch back to the
+            // same fallback type, which is only correct when there's a single such symbol); any
+            // narrowing a nested branch introduced for other symbols doesn't survive this join.
+            Some((symbol, ..)) => {
+                let fallback = self.result.symbol_ty(symbol);
+                let file = self.file();
+                let merged = NarrowedTypes::merge(
+                    self.db,
+                    file,
+                    &[(then_narrowed, fallback), (else_narrowed, fallback)],
+                );
+                // Every other symbol keeps the narrowing it had before the chain.
+                let mut narrowed = std::mem::take(&mut self.narrowed);
+                match merged.get(symbol) {
+                    Some(ty) => narrowed.set(symbol, ty),
+                    None => narrowed.clear(symbol),
+                }
+                narrowed
+            }
+            None => else_narrowed,
+        };
+    }
+
+    /// Recognizes a handful of common narrowing predicate shapes -- `isinstance(x, C)`, `x is
+    /// None`/`x is not None`, and a bare `x` truthiness test -- and returns the symbol they narrow
+    /// together with its type on the truthy and falsy sides of the test, if `test` matches one of
+    /// them and names a symbol in scope.
+    fn narrow_for_test(
+        &mut self,
+        test: &ast::Expr,
+        at: &NarrowedTypes,
+    ) -> Option<(ScopedSymbolId, Type, Type)> {
+        match test {
+            ast::Expr::Call(call) => {
+                let ast::Expr::Name(func) = call.func.as_ref() else {
+                    return None;
+                };
+                if func.id.as_str() != "isinstance" || call.arguments.args.len() != 2 {
+                    return None;
+                }
+                let ast::Expr::Name(target) = &call.arguments.args[0] else {
+                    return None;
+                };
+                let symbol = self.symbol_id(target.id.as_str())?;
+                let base = at
+                    .get(symbol)
+                    .unwrap_or_else(|| self.result.symbol_ty(symbol));
+                let class_ty = self.infer_expr(&call.arguments.args[1], Expectation::default());
+                let file = self.file();
+                Some((
+                    symbol,
+                    narrow_isinstance(self.db, file, base, class_ty, true),
+                    narrow_isinstance(self.db, file, base, class_ty, false),
+                ))
+            }
+            ast::Expr::Compare(compare) => {
+                if compare.ops.len() != 1 || compare.comparators.len() != 1 {
+                    return None;
+                }
+                let positive = match compare.ops[0] {
+                    ast::CmpOp::Is => true,
+                    ast::CmpOp::IsNot => false,
+                    _ => return None,
+                };
+                if !matches!(compare.comparators[0], ast::Expr::NoneLiteral(_)) {
+                    return None;
+                }
+                let ast::Expr::Name(target) = compare.left.as_ref() else {
+                    return None;
+                };
+                let symbol = self.symbol_id(target.id.as_str())?;
+                let base = at
+                    .get(symbol)
+                    .unwrap_or_else(|| self.result.symbol_ty(symbol));
+                let file = self.file();
+                Some((
+                    symbol,
+                    narrow_is_none(self.db, file, base, positive),
+                    narrow_is_none(self.db, file, base, !positive),
+                ))
+            }
+            ast::Expr::Name(name) => {
+                let symbol = self.symbol_id(name.id.as_str())?;
+                let base = at
+                    .get(symbol)
+                    .unwrap_or_else(|| self.result.symbol_ty(symbol));
+                Some((
+                    symbol,
+                    narrow_truthy(self.db, base, true),
+                    narrow_truthy(self.db, base, false),
+                ))
+            }
+            _ => None,
+        }
+    }
+
+    fn infer_expr(&mut self, expr: &ast::Expr, expectation: Expectation) -> Type {
+        let ty = match expr {
+            ast::Expr::NumberLiteral(node) => match &node.value {
+                ast::Number::Int(int) => match int.as_i64() {
+                    Some(value) => expectation.coerce_int_literal(self.db, Type::IntLiteral(value)),
+                    None => Type::Unknown,
+                },
+                _ => Type::Unknown,
+            },
+            ast::Expr::NoneLiteral(_) => Type::None,
+            ast::Expr::Name(node) => self.infer_name(node),
+            ast::Expr::Call(node) => self.infer_call(node),
+            _ => Type::Unknown,
+        };
+        self.record_expr_ty(expr, ty)
+    }
+
+    /// Infers a call expression's type from the callee's own type, via [`Type::call`] -- a
+    /// function call evaluates to its signature's declared (or inferred) return type, a class
+    /// call to an instance of that class. Each argument is inferred against the corresponding
+    /// parameter's declared type, the same [`Expectation`]-driven coercion an annotated assignment
+    /// gets.
+    ///
+    /// This is only the consuming side, at the call site: it reads the callee's existing
+    /// [`Signature`](crate::types::Signature). This builder does not build signatures from
+    /// function definitions.
+    fn infer_call(&mut self, call: &ast::ExprCall) -> Type {
+        let callee_ty = self.infer_expr(&call.func, Expectation::default());
+
+        let parameters: Option<&[Parameter]> = match callee_ty {
+            Type::Function(function) => Some(function.lookup(self.db).signature().parameters()),
+            Type::BoundMethod(function) => {
+                Some(function.lookup(self.db).signature().bound_parameters())
+            }
+            _ => None,
+        };
+
+        for (index, arg) in call.arguments.args.iter().enumerate() {
+            let expectation = parameters
+                .and_then(|parameters| parameters.get(index))
+                .map(|parameter| Expectation::ExpectHasType(parameter.ty()))
+                .unwrap_or_default();
+            self.infer_expr(arg, expectation);
+        }
+        for keyword in &call.arguments.keywords {
+            let expectation = keyword
+                .arg
+                .as_ref()
+                .and_then(|name| {
+                    parameters.and_then(|parameters| {
+                        parameters
+                            .iter()
+                            .find(|parameter| parameter.name() == name.as_str())
+                    })
+                })
+                .map(|parameter| Expectation::ExpectHasType(parameter.ty()))
+                .unwrap_or_default();
+            self.infer_expr(&keyword.value, expectation);
+        }
+
+        callee_ty.call(self.db)
+    }
+
+    fn infer_name(&mut self, node: &ast::ExprName) -> Type {
+        let Some(symbol) = self.symbol_id(node.id.as_str()) else {
+            return Type::Unknown;
+        };
+        self.narrowed
+            .get(symbol)
+            .unwrap_or_else(|| self.result.symbol_ty(symbol))
+    }
+
+    fn record_expr_ty(&mut self, expr: &ast::Expr, ty: Type) -> Type {
+        let file_scope = self.scope.file_scope_id(self.db);
+        let id = expr.scope_ast_id(self.db, self.file(), file_scope);
+        self.result.expression_tys.insert(id.index(), ty);
+        ty
+    }
+}
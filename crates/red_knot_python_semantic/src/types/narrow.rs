@@ -0,0 +1,99 @@
+//! Flow-sensitive type narrowing.
+//!
+//! This module holds the pure, reusable pieces of narrowing: given a symbol's type before a
+//! branch and the predicate guarding it, compute the narrowed type for each side of the branch,
+//! and how to merge the per-branch results back together at the join point. `TypeInferenceBuilder`
+//! (see `types::infer`) is responsible for recognizing which expressions are narrowing predicates,
+//! threading a per-scope `NarrowedTypes` map through statement/expression inference, and clearing
+//! entries when a symbol is reassigned; this module only knows how to turn one recognized
+//! predicate into a type transformation.
+
+use rustc_hash::{FxHashMap, FxHashSet};
+
+use ruff_db::vfs::VfsFile;
+
+use crate::semantic_index::symbol::ScopedSymbolId;
+use crate::types::{IntersectionTypeBuilder, Type, UnionTypeBuilder};
+use crate::Db;
+
+/// The narrowed type of each symbol currently in scope, as of a particular point in the control
+/// flow. Symbols with no entry have not been narrowed and keep their normal inferred type.
+#[derive(Debug, Clone, Default)]
+pub(crate) struct NarrowedTypes {
+    narrowed: FxHashMap<ScopedSymbolId, Type>,
+}
+
+impl NarrowedTypes {
+    pub(crate) fn get(&self, symbol: ScopedSymbolId) -> Option<Type> {
+        self.narrowed.get(&symbol).copied()
+    }
+
+    pub(crate) fn set(&mut self, symbol: ScopedSymbolId, ty: Type) {
+        self.narrowed.insert(symbol, ty);
+    }
+
+    /// Clears any narrowing recorded for `symbol`, e.g. because it was just reassigned.
+    pub(crate) fn clear(&mut self, symbol: ScopedSymbolId) {
+        self.narrowed.remove(&symbol);
+    }
+
+    /// Merges the narrowed types recorded along two or more reachable branches at a control-flow
+    /// join point, unioning each symbol's per-branch types back together.
+    ///
+    /// `branches` pairs each branch's `NarrowedTypes` with the symbol's fallback (un-narrowed)
+    /// type on that branch, used for any symbol this branch didn't narrow itself — since after the
+    /// join, either branch could have been taken, a symbol narrowed on only one of them still
+    /// needs its other branch's type represented in the union. A symbol absent from every branch's
+    /// `narrowed` map (narrowed on none of them) is left out of the result entirely, so it keeps
+    /// following its normal (non-narrowed) inferred type.
+    pub(crate) fn merge(db: &dyn Db, file: VfsFile, branches: &[(Self, Type)]) -> Self {
+        let mut symbols: FxHashSet<ScopedSymbolId> = FxHashSet::default();
+        for (branch, _) in branches {
+            symbols.extend(branch.narrowed.keys().copied());
+        }
+
+        let mut merged = Self::default();
+        for symbol in symbols {
+            let mut builder = UnionTypeBuilder::new(db);
+            for (branch, fallback) in branches {
+                builder = builder.add(branch.get(symbol).unwrap_or(*fallback));
+            }
+            merged.set(symbol, builder.build_ty(file));
+        }
+
+        merged
+    }
+}
+
+/// Narrows `ty` assuming `isinstance(<value of type ty>, <class>)` held (`positive = true`) or
+/// definitely did not hold (`positive = false`).
+///
+/// The positive branch intersects `ty` with `Instance(class)`; the negative branch instead adds
+/// `Instance(class)` to the negative side of an intersection, per the `IntersectionType` contract
+/// (see its doc comment in `types.rs`) that negative elements record "definitely not this type"
+/// without being directly expressible on their own.
+pub(crate) fn narrow_isinstance(db: &dyn Db, file: VfsFile, ty: Type, class: Type, positive: bool) -> Type {
+    let mut builder = IntersectionTypeBuilder::new(db);
+    builder = builder.add_positive(ty);
+    builder = if positive {
+        builder.add_positive(class)
+    } else {
+        builder.add_negative(class)
+    };
+    builder.build_ty(file)
+}
+
+/// Narrows `ty` assuming `<value of type ty> is None` held (`positive = true`) or did not
+/// (`positive = false`, i.e. `is not None`).
+pub(crate) fn narrow_is_none(db: &dyn Db, file: VfsFile, ty: Type, positive: bool) -> Type {
+    narrow_isinstance(db, file, ty, Type::None, positive)
+}
+
+/// Narrows `ty` under a bare truthiness test (`if x:` / `if not x:`).
+///
+/// Without literal-bool or `__bool__` modeling we can't narrow the type itself yet (a truthy `int`
+/// is still any `int`), so for now this is a no-op placeholder that documents where that
+/// narrowing would hook in once truthiness is modeled on `Type`.
+pub(crate) fn narrow_truthy(_db: &dyn Db, ty: Type, _positive: bool) -> Type {
+    ty
+}
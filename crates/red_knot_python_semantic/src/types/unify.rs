@@ -0,0 +1,324 @@
+//! Union-find based unification for inference variables.
+//!
+//! Modeled after rust-analyzer's `infer::unify`, which in turn wraps the `ena` crate's
+//! `UnificationTable`: each not-yet-known type is represented by a fresh [`InferId`], and
+//! constraints between expressions are recorded by unioning the variables' roots rather than
+//! eagerly deciding a concrete type. A variable's root is either still unresolved or carries the
+//! concrete [`Type`] it was unified with.
+
+use ruff_index::{newtype_index, IndexVec};
+
+use crate::types::Type;
+
+/// Uniquely identifies an inference variable allocated during a single [`super::infer::TypeInferenceBuilder`] pass.
+///
+/// `InferId`s are only meaningful relative to the [`UnificationTable`] that allocated them; unlike
+/// [`super::TypeId`], they are never persisted across salsa revisions.
+#[newtype_index]
+pub(crate) struct InferId;
+
+#[derive(Debug, Clone)]
+enum Node {
+    /// This variable is its own representative; `rank` is used to keep union-find trees shallow.
+    Root { rank: u32, value: Option<Type> },
+    /// This variable has been unioned into another one.
+    Child(InferId),
+}
+
+/// A snapshot token returned by [`UnificationTable::snapshot`].
+///
+/// Rolling back to a snapshot undoes every unification performed after it was taken, which lets
+/// speculative work (e.g. trying one overload after another) back out cleanly.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) struct Snapshot(usize);
+
+#[derive(Debug, Clone, Copy)]
+enum UndoLogEntry {
+    NewVar,
+    /// `child` and `root` used to each be their own root, with these `rank`/`value`s; restore
+    /// both. `root`'s value is only overwritten by the union when it differs from its original
+    /// (e.g. it was unresolved and `child` supplied the merged value), but we restore it
+    /// unconditionally since recomputing which case applied at rollback time would just
+    /// reimplement `unify_var_var`'s merge logic a second time.
+    Union {
+        child: InferId,
+        child_rank: u32,
+        child_value: Option<Type>,
+        root: InferId,
+        root_rank: u32,
+        root_value: Option<Type>,
+    },
+    SetValue { var: InferId, previous: Option<Type> },
+}
+
+/// Union-find table mapping inference variables to the (possibly still-unknown) type they stand for.
+#[derive(Debug, Clone, Default)]
+pub(crate) struct UnificationTable {
+    nodes: IndexVec<InferId, Node>,
+    undo_log: Vec<UndoLogEntry>,
+}
+
+impl UnificationTable {
+    pub(crate) fn new() -> Self {
+        Self::default()
+    }
+
+    /// Allocates a fresh, still-unresolved inference variable.
+    pub(crate) fn new_variable(&mut self) -> InferId {
+        self.undo_log.push(UndoLogEntry::NewVar);
+        self.nodes.push(Node::Root {
+            rank: 0,
+            value: None,
+        })
+    }
+
+    /// Finds the representative root of `var`, compressing the path as it goes.
+    fn find(&mut self, var: InferId) -> InferId {
+        match self.nodes[var] {
+            Node::Root { .. } => var,
+            Node::Child(parent) => {
+                let root = self.find(parent);
+                if root != parent {
+                    self.nodes[var] = Node::Child(root);
+                }
+                root
+            }
+        }
+    }
+
+    /// Returns the type currently assigned to `var`'s root, if any has been resolved yet.
+    pub(crate) fn probe(&mut self, var: InferId) -> Option<Type> {
+        let root = self.find(var);
+        match &self.nodes[root] {
+            Node::Root { value, .. } => value.clone(),
+            Node::Child(_) => unreachable!("find() always returns a root"),
+        }
+    }
+
+    /// Unifies `var`'s root with the concrete `ty`.
+    ///
+    /// Returns `false` (and leaves the table unchanged) if `ty` transitively contains `var`,
+    /// which would otherwise make resolution loop forever.
+    pub(crate) fn unify_var_value(&mut self, var: InferId, ty: Type) -> bool {
+        if self.occurs_in(var, &ty) {
+            return false;
+        }
+        let root = self.find(var);
+        let Node::Root { rank, value } = &mut self.nodes[root] else {
+            unreachable!("find() always returns a root");
+        };
+        let rank = *rank;
+        let previous = value.clone();
+        self.nodes[root] = Node::Root {
+            rank,
+            value: Some(ty),
+        };
+        self.undo_log.push(UndoLogEntry::SetValue {
+            var: root,
+            previous,
+        });
+        true
+    }
+
+    /// Unions the roots of `a` and `b`.
+    ///
+    /// If exactly one side already has a concrete value, the merged root keeps it. If both sides
+    /// have *different* concrete values, unification fails (the caller should fall back to
+    /// `Unknown`); this function returns whether unification succeeded.
+    pub(crate) fn unify_var_var(&mut self, a: InferId, b: InferId) -> bool {
+        let root_a = self.find(a);
+        let root_b = self.find(b);
+        if root_a == root_b {
+            return true;
+        }
+
+        let Node::Root {
+            rank: rank_a,
+            value: value_a,
+        } = self.nodes[root_a].clone()
+        else {
+            unreachable!()
+        };
+        let Node::Root {
+            rank: rank_b,
+            value: value_b,
+        } = self.nodes[root_b].clone()
+        else {
+            unreachable!()
+        };
+
+        let merged_value = match (value_a, value_b) {
+            (Some(a), Some(b)) if a != b => return false,
+            (Some(a), _) => Some(a),
+            (None, b) => b,
+        };
+
+        // Union by rank: attach the shallower tree under the deeper one.
+        let (new_root, child, new_rank) = if rank_a >= rank_b {
+            (root_a, root_b, rank_a.max(rank_b + 1))
+        } else {
+            (root_b, root_a, rank_b.max(rank_a + 1))
+        };
+
+        let (child_rank, child_value, root_rank, root_value) = if child == root_a {
+            (rank_a, value_a, rank_b, value_b)
+        } else {
+            (rank_b, value_b, rank_a, value_a)
+        };
+
+        self.undo_log.push(UndoLogEntry::Union {
+            child,
+            child_rank,
+            child_value,
+            root: new_root,
+            root_rank,
+            root_value,
+        });
+        self.nodes[child] = Node::Child(new_root);
+        self.nodes[new_root] = Node::Root {
+            rank: new_rank,
+            value: merged_value,
+        };
+
+        true
+    }
+
+    /// Occurs-check: does `ty` transitively reference the inference variable `var`?
+    fn occurs_in(&mut self, var: InferId, ty: &Type) -> bool {
+        match ty {
+            Type::Infer(other) => self.find(*other) == self.find(var),
+            Type::Union(_) | Type::Intersection(_) => {
+                // Union/intersection members are themselves already-resolved `Type`s at the point
+                // we unify, so there's nothing further to recurse into here; a variable can only
+                // appear directly, not nested inside an already-built union/intersection.
+                false
+            }
+            _ => false,
+        }
+    }
+
+    /// Records a point to which we might later [`rollback`](Self::rollback).
+    pub(crate) fn snapshot(&self) -> Snapshot {
+        Snapshot(self.undo_log.len())
+    }
+
+    /// Undoes every unification performed since `snapshot` was taken.
+    pub(crate) fn rollback(&mut self, snapshot: Snapshot) {
+        while self.undo_log.len() > snapshot.0 {
+            match self.undo_log.pop().unwrap() {
+                UndoLogEntry::NewVar => {
+                    self.nodes.pop();
+                }
+                UndoLogEntry::Union {
+                    child,
+                    child_rank,
+                    child_value,
+                    root,
+                    root_rank,
+                    root_value,
+                } => {
+                    self.nodes[child] = Node::Root {
+                        rank: child_rank,
+                        value: child_value,
+                    };
+                    self.nodes[root] = Node::Root {
+                        rank: root_rank,
+                        value: root_value,
+                    };
+                }
+                UndoLogEntry::SetValue { var, previous } => {
+                    if let Node::Root { rank, .. } = self.nodes[var] {
+                        self.nodes[var] = Node::Root {
+                            rank,
+                            value: previous,
+                        };
+                    }
+                }
+            }
+        }
+    }
+
+    /// Resolves `var` to a concrete type, substituting [`Type::Unknown`] if it was never unified
+    /// with anything concrete.
+    pub(crate) fn resolve(&mut self, var: InferId) -> Type {
+        self.probe(var).unwrap_or(Type::Unknown)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn fresh_variable_resolves_unknown() {
+        let mut table = UnificationTable::new();
+        let var = table.new_variable();
+        assert_eq!(table.resolve(var), Type::Unknown);
+    }
+
+    #[test]
+    fn unify_var_value_resolves_to_that_value() {
+        let mut table = UnificationTable::new();
+        let var = table.new_variable();
+        assert!(table.unify_var_value(var, Type::IntLiteral(5)));
+        assert_eq!(table.resolve(var), Type::IntLiteral(5));
+    }
+
+    #[test]
+    fn unify_var_var_propagates_value_to_both_sides() {
+        let mut table = UnificationTable::new();
+        let a = table.new_variable();
+        let b = table.new_variable();
+        assert!(table.unify_var_value(b, Type::IntLiteral(5)));
+        assert!(table.unify_var_var(a, b));
+        assert_eq!(table.resolve(a), Type::IntLiteral(5));
+        assert_eq!(table.resolve(b), Type::IntLiteral(5));
+    }
+
+    #[test]
+    fn unify_var_var_fails_on_conflicting_values() {
+        let mut table = UnificationTable::new();
+        let a = table.new_variable();
+        let b = table.new_variable();
+        assert!(table.unify_var_value(a, Type::IntLiteral(1)));
+        assert!(table.unify_var_value(b, Type::IntLiteral(2)));
+        assert!(!table.unify_var_var(a, b));
+    }
+
+    #[test]
+    fn unify_var_value_rejects_self_referential_occurs_check() {
+        let mut table = UnificationTable::new();
+        let var = table.new_variable();
+        assert!(!table.unify_var_value(var, Type::Infer(var)));
+        assert_eq!(table.resolve(var), Type::Unknown);
+    }
+
+    #[test]
+    fn rollback_undoes_unify_var_value() {
+        let mut table = UnificationTable::new();
+        let var = table.new_variable();
+        let snapshot = table.snapshot();
+        assert!(table.unify_var_value(var, Type::IntLiteral(5)));
+        table.rollback(snapshot);
+        assert_eq!(table.resolve(var), Type::Unknown);
+    }
+
+    /// Regression test: rolling back a `unify_var_var` must restore *both* sides to their
+    /// pre-union state, not just the side that became the `Child`. Before this was fixed, the
+    /// surviving root kept whatever value the union merged in even after rollback.
+    #[test]
+    fn rollback_undoes_unify_var_var_on_both_sides() {
+        let mut table = UnificationTable::new();
+        let a = table.new_variable();
+        let b = table.new_variable();
+        assert!(table.unify_var_value(b, Type::IntLiteral(5)));
+
+        let snapshot = table.snapshot();
+        assert!(table.unify_var_var(a, b));
+        assert_eq!(table.resolve(a), Type::IntLiteral(5));
+        table.rollback(snapshot);
+
+        assert_eq!(table.resolve(a), Type::Unknown);
+        assert_eq!(table.resolve(b), Type::IntLiteral(5));
+    }
+}